@@ -1,16 +1,14 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    StdError, StdResult,
+    StdError, StdResult, SubMsgResult,
 };
 
 use crate::{
     error::ContractError,
     msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-    state::{Config, CONFIG},
+    state::{Config, FailurePolicy, CONFIG, PENDING_MATCHES, PENDING_SETTLEMENTS},
 };
 
-pub const CONFIRM_ORDER_REPLY_ID: u64 = 1;
-
 const CONTRACT_NAME: &str = "crates.io/cw-atomic-swap";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -28,7 +26,19 @@ pub fn instantiate(
         .api
         .addr_validate(&msg.owner.unwrap_or(info.sender.to_string()))?;
 
-    CONFIG.save(deps.storage, &Config { owner })?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner,
+            check_maker_balance: false,
+            failure_policy: FailurePolicy::default(),
+            taker_group: None,
+            compliance_hook: None,
+            receipt_contract: None,
+            reservation_policy: None,
+            suspension_rule: None,
+        },
+    )?;
 
     Ok(Response::new())
 }
@@ -42,19 +52,200 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     use ExecuteMsg::*;
     match msg {
-        UpdateConfig { new_owner } => execute::update_config(deps, env, &info.sender, new_owner),
+        UpdateConfig {
+            new_owner,
+            check_maker_balance,
+            failure_policy,
+            taker_group,
+            compliance_hook,
+            receipt_contract,
+            reservation_policy,
+            suspension_rule,
+        } => execute::update_config(
+            deps,
+            env,
+            &info.sender,
+            new_owner,
+            check_maker_balance,
+            failure_policy,
+            taker_group,
+            compliance_hook,
+            receipt_contract,
+            reservation_policy,
+            suspension_rule,
+        ),
         CreateSwapOrder {
+            maker,
             coin_in,
             coin_out,
-            taker,
+            allowed_takers,
+            taker_merkle_root,
+            taker_group,
+            mint_receipt,
+            reserved_for,
+            timeout,
+        } => execute::create_swap_order(
+            deps,
+            env,
+            info,
+            maker,
+            coin_in,
+            coin_out,
+            allowed_takers,
+            taker_merkle_root,
+            taker_group,
+            mint_receipt,
+            reserved_for,
+            timeout,
+        ),
+        CreateDutchSwapOrder {
+            maker,
+            coin_in,
+            coin_out,
+            end_amount,
+            step,
+            allowed_takers,
+            timeout,
+        } => execute::create_dutch_swap_order(
+            deps,
+            env,
+            info,
+            maker,
+            coin_in,
+            coin_out,
+            end_amount,
+            step,
+            allowed_takers,
+            timeout,
+        ),
+        CreateAuctionSwapOrder {
+            maker,
+            coin_in,
+            coin_out,
+            min_increment,
+            extension,
+            timeout,
+        } => execute::create_auction_swap_order(
+            deps,
+            env,
+            info,
+            maker,
+            coin_in,
+            coin_out,
+            min_increment,
+            extension,
+            timeout,
+        ),
+        BidSwapOrder { order_id, maker } => {
+            execute::bid_swap_order(deps, info, env, order_id, maker)
+        }
+        SettleAuction { order_id, maker } => {
+            execute::settle_auction(deps, info, env, order_id, maker)
+        }
+        CreateQuoteRequest {
+            want,
+            offer_denom,
+            expiry,
+        } => execute::create_quote_request(deps, env, info, want, offer_denom, expiry),
+        CancelQuoteRequest { request_id } => execute::cancel_quote_request(deps, info, request_id),
+        SubmitQuote {
+            request_id,
+            maker,
+            price,
+            timeout,
+        } => execute::submit_quote(deps, env, info, request_id, maker, price, timeout),
+        AcceptQuote {
+            request_id,
+            maker,
+            order_id,
+        } => execute::accept_quote(deps, info, env, request_id, maker, order_id),
+        CreateSwapOrders { maker, orders } => {
+            execute::create_swap_orders(deps, env, info, maker, orders)
+        }
+        AmendSwapOrder {
+            order_id,
+            maker,
+            price,
             timeout,
-        } => execute::create_swap_order(deps, env, info, coin_in, coin_out, taker, timeout),
-        AcceptSwapOrder { order_id, maker } => {
-            execute::accept_swap_order(deps, info, env, order_id, maker)
+        } => execute::amend_swap_order(deps, env, info, order_id, maker, price, timeout),
+        CancelSwapOrders { maker, order_ids } => {
+            execute::cancel_swap_orders(deps, env, info, maker, order_ids)
+        }
+        CancelAllMyOrders { maker, denom_pair } => {
+            execute::cancel_all_my_orders(deps, env, info, maker, denom_pair)
+        }
+        MatchOrders {
+            order,
+            counter_order,
+            proof,
+            counter_proof,
+        } => execute::match_orders(deps, info, env, order, counter_order, proof, counter_proof),
+        MakeCounterOffer {
+            order_id,
+            maker,
+            expiry,
+            proof,
+        } => execute::make_counter_offer(deps, info, env, order_id, maker, expiry, proof),
+        WithdrawCounterOffer { order_id, maker } => {
+            execute::withdraw_counter_offer(deps, info, order_id, maker)
+        }
+        AcceptCounterOffer {
+            order_id,
+            taker,
+            proof,
+        } => execute::accept_counter_offer(deps, info, env, order_id, taker, proof),
+        RefundCounterOffers { order_id, maker } => {
+            execute::refund_expired_counter_offers(deps, info, env, order_id, maker)
         }
+        ReserveSwapOrder {
+            order_id,
+            maker,
+            duration,
+            proof,
+        } => execute::reserve_swap_order(deps, info, env, order_id, maker, duration, proof),
+        AcceptSwapOrder {
+            order_id,
+            maker,
+            proof,
+        } => execute::accept_swap_order(deps, info, env, order_id, maker, proof),
+        AcceptSwapOrders {
+            orders,
+            mode,
+            proofs,
+        } => execute::accept_swap_orders(deps, info, env, orders, mode, proofs),
         ConfirmSwapOrder { order_id, maker } => {
             execute::confirm_swap_order(deps, info, env, order_id, maker)
         }
+        CreateHtlcOrder {
+            coin_in,
+            coin_out,
+            taker,
+            hash_lock,
+            time_lock,
+            timeout,
+        } => execute::create_htlc_order(
+            deps, env, info, coin_in, coin_out, taker, hash_lock, time_lock, timeout,
+        ),
+        LockHtlcOrder { order_id, maker } => {
+            execute::lock_htlc_order(deps, info, env, order_id, maker)
+        }
+        ClaimHtlcOrder { order_id, preimage } => {
+            execute::claim_htlc_order(deps, info, env, order_id, preimage)
+        }
+        RefundHtlcOrder { order_id, maker } => {
+            execute::refund_htlc_order(deps, info, env, order_id, maker)
+        }
+        CancelHtlcOrder { order_id } => execute::cancel_htlc_order(deps, info, order_id),
+        DepositBond { order_id } => execute::deposit_bond(deps, env, info, order_id),
+        WithdrawBond { order_id } => execute::withdraw_bond(deps, env, info, order_id),
+        BlockTakers { takers } => execute::block_takers(deps, info, takers),
+        UnblockTakers { takers } => execute::unblock_takers(deps, info, takers),
+        ApproveOperator {
+            operator,
+            permissions,
+            expires,
+        } => execute::approve_operator(deps, env, info, operator, permissions, expires),
+        RevokeOperator { operator } => execute::revoke_operator(deps, info, operator),
     }
 }
 
@@ -67,240 +258,2882 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         SwapOrdersByMaker { maker } => {
             to_json_binary(&query::get_orders_by_maker(deps, env, maker)?)
         }
+        HtlcOrdersByMaker { maker } => {
+            to_json_binary(&query::get_htlc_orders_by_maker(deps, maker)?)
+        }
+        MakerExposure { maker } => to_json_binary(&query::get_maker_exposure(deps, env, maker)?),
+        MakerBond { maker } => to_json_binary(&query::get_maker_bond(deps, maker)?),
+        MakerStats { maker } => to_json_binary(&query::get_maker_stats(deps, env, maker)?),
+        BlockedTakers { maker } => to_json_binary(&query::get_blocked_takers(deps, maker)?),
+        Operators { maker } => to_json_binary(&query::get_operators(deps, maker)?),
+        OpenQuoteRequests {} => to_json_binary(&query::get_open_quote_requests(deps, env)?),
+        QuotesByRequest { request_id } => {
+            to_json_binary(&query::get_quotes_by_request(deps, env, request_id)?)
+        }
+        CounterOffers { maker, order_id } => {
+            to_json_binary(&query::get_counter_offers(deps, maker, order_id)?)
+        }
+        CurrentPrice { maker, order_id } => {
+            to_json_binary(&query::get_current_price(deps, env, maker, order_id)?)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
-    deps.api
-        .debug("Failed execution, entered in reply entry point");
-    match msg.id {
-        CONFIRM_ORDER_REPLY_ID => reply::reply_confirm_order(deps),
-        _ => Err(StdError::generic_err(format!("received unkown reply id: {}", msg.id)).into()),
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    deps.api.debug("Entered in reply entry point");
+    if let Some(order_pointers) = PENDING_SETTLEMENTS.may_load(deps.storage, msg.id)? {
+        PENDING_SETTLEMENTS.remove(deps.storage, msg.id);
+        return match msg.result {
+            SubMsgResult::Ok(_) => Ok(Response::new()),
+            SubMsgResult::Err(err) => reply::reply_confirm_orders(deps, env, order_pointers, err),
+        };
+    }
+
+    let order_pointers = PENDING_MATCHES
+        .may_load(deps.storage, msg.id)?
+        .ok_or_else(|| StdError::generic_err(format!("received unkown reply id: {}", msg.id)))?;
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            PENDING_MATCHES.remove(deps.storage, msg.id);
+            Ok(Response::new())
+        }
+        SubMsgResult::Err(err) => reply::reply_match_orders(deps, env, msg.id, order_pointers, err),
     }
 }
 
 pub mod execute {
-    use cosmwasm_std::{ensure, Addr, BankMsg, Coin, CosmosMsg, SubMsg};
+    use std::collections::BTreeMap;
+
+    use cosmwasm_std::{
+        ensure, Addr, BankMsg, Coin, CosmosMsg, Decimal, Empty, Event, HexBinary, Order, Storage,
+        SubMsg, Uint128, WasmMsg,
+    };
+    use cw721::OwnerOfResponse;
+    use cw721_base::{ExecuteMsg as Cw721ExecuteMsg, Extension, QueryMsg as Cw721QueryMsg};
+    use cw_storage_plus::Bound;
     use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
 
-    use crate::state::{next_id, OrderPointer, OrderStatus, SwapOrder, ORDER_POINTER, SWAP_ORDERS};
+    use crate::msg::{
+        AcceptMode, AcceptSwapOrderResult, AcceptSwapOrdersResponse, ComplianceAction,
+        ComplianceHookUpdate, ReceiptContractUpdate, ReservationPolicyUpdate, SuspensionRuleUpdate,
+        SwapOrderParams, TakerGroupParams, TakerGroupUpdate,
+    };
+
+    use crate::merkle::verify_proof;
+    use crate::state::{
+        add_to_order_book, commit_exposure, next_id, next_quote_request_id, next_reply_id,
+        refund_counter_offers, release_expired_exposure, release_exposure, release_reservation,
+        remove_from_order_book, update_maker_stats, CounterOffer, HtlcOrder, OperatorApproval,
+        OperatorPermission, OrderKind, OrderPhase, OrderPointer, OrderStatus, QuoteRequest,
+        Reservation, SwapOrder, TakerGroup, ACCEPTED_OFFERS, BLOCKED_TAKERS, COMMITTED_ORDERS,
+        COUNTER_OFFERS, HTLC_ORDERS, MAKER_BONDS, MAKER_EXPOSURE, MAKER_STATS, MATCHED_ORDERS,
+        OPERATORS, ORDER_BONDS, ORDER_BOOK, PENDING_MATCHES, QUOTES, QUOTE_REQUESTS, SWAP_ORDERS,
+    };
     use crate::utils::{
-        check_correct_coins, create_authz_encoded_message, validate_coins_number,
-        validate_different_denoms, validate_native_denom, validate_status_and_expiration,
+        check_correct_coins, check_sufficient_coins, create_authz_encoded_message, current_price,
+        query_authz_spend_limit, validate_coins_number, validate_compliance,
+        validate_different_denoms, validate_group_member, validate_hash_lock,
+        validate_native_denom, validate_preimage, validate_quote_request_open,
+        validate_status_and_expiration, MAX_ALLOWED_TAKERS, MAX_COUNTER_OFFERS,
+        MAX_CROSSING_CANDIDATES, MAX_QUOTES_PER_REQUEST,
     };
 
     use super::*;
 
     /// Allows to update the atomic swap market configuration.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_config(
         deps: DepsMut,
         _env: Env,
         sender: &Addr,
-        new_owner: String,
+        new_owner: Option<String>,
+        check_maker_balance: Option<bool>,
+        failure_policy: Option<FailurePolicy>,
+        taker_group: Option<TakerGroupUpdate>,
+        compliance_hook: Option<ComplianceHookUpdate>,
+        receipt_contract: Option<ReceiptContractUpdate>,
+        reservation_policy: Option<ReservationPolicyUpdate>,
+        suspension_rule: Option<SuspensionRuleUpdate>,
     ) -> Result<Response, ContractError> {
         let mut config = CONFIG.load(deps.storage)?;
         ensure!(config.owner == sender, ContractError::Unauthorized);
-        config.owner = deps.api.addr_validate(&new_owner)?;
+
+        let mut response = Response::new().add_attribute("action", "update_config");
+        if let Some(new_owner) = new_owner {
+            config.owner = deps.api.addr_validate(&new_owner)?;
+            response = response.add_attribute("new_owner", new_owner);
+        }
+        if let Some(check_maker_balance) = check_maker_balance {
+            config.check_maker_balance = check_maker_balance;
+            response =
+                response.add_attribute("check_maker_balance", check_maker_balance.to_string());
+        }
+        if let Some(failure_policy) = failure_policy {
+            if let FailurePolicy::RefundAndPenalize { penalty } = &failure_policy {
+                validate_native_denom(&penalty.denom)?;
+                ensure!(!penalty.amount.is_zero(), ContractError::ZeroPenalty {});
+            }
+            response = response.add_attribute("failure_policy", failure_policy.to_string());
+            config.failure_policy = failure_policy;
+        }
+        match taker_group {
+            Some(TakerGroupUpdate::Set(params)) => {
+                let taker_group = validate_taker_group(deps.as_ref(), params)?;
+                response = response.add_attribute("taker_group", &taker_group.group);
+                config.taker_group = Some(taker_group);
+            }
+            Some(TakerGroupUpdate::Remove {}) => {
+                response = response.add_attribute("taker_group", "none");
+                config.taker_group = None;
+            }
+            None => {}
+        }
+        match compliance_hook {
+            Some(ComplianceHookUpdate::Set(compliance_hook)) => {
+                let compliance_hook = deps.api.addr_validate(&compliance_hook)?;
+                response = response.add_attribute("compliance_hook", &compliance_hook);
+                config.compliance_hook = Some(compliance_hook);
+            }
+            Some(ComplianceHookUpdate::Remove {}) => {
+                response = response.add_attribute("compliance_hook", "none");
+                config.compliance_hook = None;
+            }
+            None => {}
+        }
+        match receipt_contract {
+            Some(ReceiptContractUpdate::Set(receipt_contract)) => {
+                let receipt_contract = deps.api.addr_validate(&receipt_contract)?;
+                response = response.add_attribute("receipt_contract", &receipt_contract);
+                config.receipt_contract = Some(receipt_contract);
+            }
+            Some(ReceiptContractUpdate::Remove {}) => {
+                response = response.add_attribute("receipt_contract", "none");
+                config.receipt_contract = None;
+            }
+            None => {}
+        }
+        match reservation_policy {
+            Some(ReservationPolicyUpdate::Set(policy)) => {
+                validate_native_denom(&policy.deposit.denom)?;
+                ensure!(
+                    !policy.deposit.amount.is_zero(),
+                    ContractError::ZeroReservationDeposit {}
+                );
+                response =
+                    response.add_attribute("reservation_deposit", policy.deposit.to_string());
+                config.reservation_policy = Some(policy);
+            }
+            Some(ReservationPolicyUpdate::Remove {}) => {
+                response = response.add_attribute("reservation_deposit", "none");
+                config.reservation_policy = None;
+            }
+            None => {}
+        }
+        match suspension_rule {
+            Some(SuspensionRuleUpdate::Set(rule)) => {
+                ensure!(
+                    rule.max_failure_rate <= Decimal::one() && rule.window != 0,
+                    ContractError::InvalidSuspensionRule {}
+                );
+                response =
+                    response.add_attribute("max_failure_rate", rule.max_failure_rate.to_string());
+                config.suspension_rule = Some(rule);
+            }
+            Some(SuspensionRuleUpdate::Remove {}) => {
+                response = response.add_attribute("max_failure_rate", "none");
+                config.suspension_rule = None;
+            }
+            None => {}
+        }
 
         CONFIG.save(deps.storage, &config)?;
-        Ok(Response::new()
-            .add_attribute("action", "update_config")
-            .add_attribute("new_owner", new_owner))
+        Ok(response)
     }
 
     /// Create a new atomic swap order.
     ///
     /// # Errors
     ///
-    ///- `coin_in` and `coin_out` are the same.
-    /// - coins to swap are not native.
     /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to create orders.
+    /// - any of the errors of `create_order`.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_swap_order(
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
+        maker: Option<String>,
         coin_in: Coin,
         coin_out: Coin,
-        taker: Option<String>,
+        allowed_takers: Vec<String>,
+        taker_merkle_root: Option<HexBinary>,
+        taker_group: Option<TakerGroupParams>,
+        mint_receipt: bool,
+        reserved_for: Option<u64>,
         timeout: u64,
     ) -> Result<Response, ContractError> {
-        validate_different_denoms(&coin_in.denom, &coin_out.denom)?;
-        validate_native_denom(&coin_in.denom)?;
-        validate_native_denom(&coin_out.denom)?;
         validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CreateOrders,
+        )?;
 
-        let taker = taker
-            .as_ref()
-            .map(|addr| deps.api.addr_validate(addr))
-            .transpose()?;
+        let order_id = create_order(
+            deps.branch(),
+            &env,
+            &maker,
+            SwapOrderParams {
+                coin_in,
+                coin_out,
+                allowed_takers,
+                taker_merkle_root,
+                taker_group,
+                mint_receipt,
+                reserved_for,
+                timeout,
+            },
+            OrderKind::Fixed,
+            None,
+        )?;
 
-        let swap_order = SwapOrder {
-            coin_in,
-            coin_out,
-            taker,
-            timeout: env.block.time.plus_seconds(timeout).seconds(),
-            status: OrderStatus::Open,
-        };
+        let mut response = Response::new()
+            .add_attribute("action", "create_swap_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", &maker);
+        if let Some(msg) = mint_receipt_msg(deps.storage, &maker, order_id)? {
+            response = response.add_message(msg);
+        }
+        if let Some((msgs, event)) = cross_order(deps, &env, &maker, order_id)? {
+            response = response.add_submessages(msgs).add_event(event);
+        }
+        Ok(response)
+    }
 
-        let order_id: u64 = next_id(deps.storage)?;
-        SWAP_ORDERS.save(deps.storage, (&info.sender, order_id), &swap_order)?;
+    /// Create a new atomic swap order whose price decreases from `coin_out` to
+    /// `end_amount` at the order timeout.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to create orders.
+    /// - `end_amount` is zero or not lower than the `coin_out` amount.
+    /// - `step` is zero.
+    /// - any of the errors of `create_order`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_dutch_swap_order(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        maker: Option<String>,
+        coin_in: Coin,
+        coin_out: Coin,
+        end_amount: Uint128,
+        step: Option<u64>,
+        allowed_takers: Vec<String>,
+        timeout: u64,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CreateOrders,
+        )?;
+        if end_amount.is_zero() || end_amount >= coin_out.amount {
+            return Err(ContractError::InvalidEndAmount {
+                start_amount: coin_out.amount.u128(),
+                end_amount: end_amount.u128(),
+            });
+        }
+        ensure!(step != Some(0), ContractError::ZeroStep {});
+
+        let kind = OrderKind::Dutch {
+            start_amount: coin_out.amount,
+            end_amount,
+            start_time: env.block.time.seconds(),
+            step,
+        };
+        // Dutch orders are not crossed since their price changes over time.
+        let order_id = create_order(
+            deps,
+            &env,
+            &maker,
+            SwapOrderParams {
+                coin_in,
+                coin_out,
+                allowed_takers,
+                taker_merkle_root: None,
+                taker_group: None,
+                mint_receipt: false,
+                reserved_for: None,
+                timeout,
+            },
+            kind,
+            None,
+        )?;
 
         Ok(Response::new()
-            .add_attribute("action", "create_swap_order")
+            .add_attribute("action", "create_dutch_swap_order")
             .add_attribute("order_id", order_id.to_string())
-            .add_attribute("maker", info.sender))
+            .add_attribute("maker", maker))
     }
 
-    // Accept a swap order.
-    //
-    // # Errors
-    //
-    // - more than one coin is sent to the contract.
-    // - sender is equal to matching order maker.
-    // - selected order is not open or timed out.
-    // - sent coin doesn't match maker wanted coin.
-    // - sender is not the specified taker if specified.
-    pub fn accept_swap_order(
+    /// Create a new auction of `coin_in`. Bids are placed in the `coin_out` denom
+    /// starting from the `coin_out` amount.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to create orders.
+    /// - `min_increment` is zero.
+    /// - any of the errors of `create_order`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_auction_swap_order(
         deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        maker: Option<String>,
+        coin_in: Coin,
+        coin_out: Coin,
+        min_increment: Uint128,
+        extension: Option<u64>,
+        timeout: u64,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CreateOrders,
+        )?;
+        ensure!(!min_increment.is_zero(), ContractError::ZeroBidIncrement {});
+
+        let kind = OrderKind::English {
+            reserve_price: coin_out.amount,
+            min_increment,
+            extension,
+            bidder: None,
+        };
+        let order_id = create_order(
+            deps,
+            &env,
+            &maker,
+            SwapOrderParams {
+                coin_in,
+                coin_out,
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                mint_receipt: false,
+                reserved_for: None,
+                timeout,
+            },
+            kind,
+            None,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "create_auction_swap_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker))
+    }
+
+    /// Place a bid on an auction. The bid is escrowed in the contract and the
+    /// previous highest bid is refunded.
+    ///
+    /// # Errors
+    ///
+    /// - more than one coin is sent to the contract.
+    /// - sender is the maker of the auction.
+    /// - sender is blocked by the maker, outside the market taker group or
+    ///   rejected by the compliance hook.
+    /// - the order is not an auction.
+    /// - the auction is not open or ended.
+    /// - the bid is lower than the reserve price or the highest bid plus the
+    ///   minimum increment.
+    /// - any of the errors of `validate_order_funding` when the bid extends the
+    ///   auction.
+    pub fn bid_swap_order(
+        mut deps: DepsMut,
         info: MessageInfo,
         env: Env,
         order_id: u64,
         maker: String,
     ) -> Result<Response, ContractError> {
-        deps.api.debug("Initiate acceptance of swap order");
-
         validate_coins_number(&info.funds, 1)?;
 
         // We don't care about validation because the address is used to match a key.
         let maker = Addr::unchecked(maker);
-        if info.sender == maker {
-            return Err(ContractError::SenderIsMaker {});
+        validate_taker(deps.as_ref(), &maker, &info.sender)?;
+
+        let mut order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+        let block_time = env.block.time.seconds();
+        validate_status_and_expiration(&order, OrderStatus::Open, block_time)?;
+        let min_bid = current_price(&order, block_time);
+        let OrderKind::English {
+            extension, bidder, ..
+        } = &mut order.kind
+        else {
+            return Err(ContractError::NotAnAuction {});
+        };
+        let bid = info.funds[0].clone();
+        check_sufficient_coins(&bid, &min_bid)?;
+
+        let mut msgs = vec![];
+        if let Some(previous_bidder) = bidder.replace(info.sender.clone()) {
+            msgs.push(BankMsg::Send {
+                to_address: previous_bidder.to_string(),
+                amount: vec![order.coin_out.clone()],
+            });
+        }
+        order.coin_out = bid.clone();
+
+        // Extend the auction if the bid is placed close to its end. The
+        // exposure is indexed by timeout, so it is committed again after
+        // checking that the maker grant covers the new end.
+        if let Some(extension) = *extension {
+            if order.timeout - block_time < extension {
+                release_exposure(deps.storage, &maker, order_id, &order)?;
+                order.timeout = block_time + extension;
+                validate_order_funding(deps.branch(), &env, &maker, &order.coin_in, order.timeout)?;
+                commit_exposure(deps.storage, &maker, order_id, &order)?;
+            }
         }
+        SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "bid_swap_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker)
+            .add_attribute("bidder", info.sender)
+            .add_attribute("bid", bid.to_string())
+            .add_attribute("end", order.timeout.to_string()))
+    }
+
+    /// Settle an ended auction with the highest bidder. The maker sends `coin_in`
+    /// through `x/authz` as for an accepted order. If the compliance hook now
+    /// rejects the highest bidder, the bid is refunded and the auction fails.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - the order is not an auction.
+    /// - the auction is not open or not ended.
+    /// - the auction has no bids.
+    pub fn settle_auction(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
 
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
         let mut order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+        let OrderKind::English { bidder, .. } = &mut order.kind else {
+            return Err(ContractError::NotAnAuction {});
+        };
+        if order.status != OrderStatus::Open {
+            return Err(ContractError::SwapOrderNotAvailable {
+                status: order.status.to_string(),
+                expiration: order.timeout,
+            });
+        }
+        if order.timeout >= env.block.time.seconds() {
+            return Err(ContractError::AuctionNotEnded { end: order.timeout });
+        }
+        let bidder = bidder.take().ok_or(ContractError::AuctionHasNoBids {})?;
 
-        validate_status_and_expiration(&order, OrderStatus::Open, env.block.time.seconds())?;
-        check_correct_coins(&info.funds[0], &order.coin_out)?;
+        // The winner is screened again since the compliance hook may reject it
+        // after its bid. A rejected winner is refunded and the auction is closed.
+        let compliance_hook = CONFIG.load(deps.storage)?.compliance_hook;
+        match validate_compliance(
+            &deps.querier,
+            compliance_hook.as_ref(),
+            &bidder,
+            ComplianceAction::AcceptSwapOrder,
+        ) {
+            Err(err @ ContractError::AddressNotAllowed { .. }) => {
+                order.status = OrderStatus::Failed;
+                order.failure_reason = Some(err.to_string());
+                SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+                release_exposure(deps.storage, &maker, order_id, &order)?;
 
-        // Check if the order is reserved and the sender is not the lucky one.
-        if let Some(taker) = order.taker {
-            if taker != info.sender {
-                return Err(ContractError::Unauthorized {});
+                return Ok(Response::new()
+                    .add_attribute("action", "settle_auction")
+                    .add_attribute("order_id", order_id.to_string())
+                    .add_attribute("maker", maker)
+                    .add_attribute("bidder", bidder.as_str())
+                    .add_attribute("failure_reason", err.to_string())
+                    .add_message(BankMsg::Send {
+                        to_address: bidder.into_string(),
+                        amount: vec![order.coin_out],
+                    }));
             }
+            result => result?,
         }
 
-        order.taker = Some(info.sender.clone());
+        order.taker = Some(bidder.clone());
         order.status = OrderStatus::Accepted;
-
         SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
 
-        // Save a pointer used in `ConfirmSwapOrder`.
-        // NOTE: the execution is atomic so it should
-        // not be required. Left for security reason but should
-        // be investigated.
-        ORDER_POINTER.save(
+        // The failure policy refunds the escrowed bid if the settlement fails.
+        let reply_id = next_reply_id(deps.storage)?;
+        PENDING_SETTLEMENTS.save(
             deps.storage,
-            &OrderPointer {
+            reply_id,
+            &vec![OrderPointer {
                 maker: maker.clone(),
-                taker: info.sender,
+                taker: bidder.clone(),
                 order_id,
-            },
+            }],
         )?;
 
-        // Create encoded `x/authz` message to trigger `ConfirmSwapOrder`
-        // on behalf of the order maker.
         let msg_exec = create_authz_encoded_message(
             env.contract.address.to_string(),
-            order_id,
             maker.to_string(),
-            order.coin_in,
+            vec![(order_id, order.coin_in)],
         )?;
         let authz_msg: CosmosMsg = CosmosMsg::Stargate {
             type_url: MsgExec::TYPE_URL.to_string(),
             value: msg_exec.into(),
         };
-        // TODO: reply on error to take action in case in which the maker
-        // doesn't have required funds. What to do? Let's see in the future.
-        let msg = SubMsg::reply_on_error(authz_msg, CONFIRM_ORDER_REPLY_ID);
 
         Ok(Response::new()
-            .add_attribute("action", "accept_swap_order")
-            .add_attribute("order_taker", order.taker.unwrap())
-            .add_submessage(msg))
+            .add_attribute("action", "settle_auction")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker)
+            .add_attribute("bidder", bidder)
+            .add_submessage(SubMsg::reply_always(authz_msg, reply_id)))
     }
 
-    /// This function complete the execution of an order between a maker and a taker.
-    /// The logic is executed via `x/authz` after receiving a `MsgExec` from this
-    /// contract.
-    //
-    // # Errors
-    //
-    // - more than one coin is sent to the contract.
-    // - sender is not the maker of the order.
-    // - selected order is not open or timed out.
-    // - sent coin doesn't match maker wanted coin.
-    // - sender is not the specified taker if specified.
-    pub fn confirm_swap_order(
+    /// Create a new request for quote.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - `want` and `offer_denom` are the same denom.
+    /// - denoms are not native.
+    pub fn create_quote_request(
         deps: DepsMut,
-        info: MessageInfo,
         env: Env,
-        order_id: u64,
-        maker: String,
+        info: MessageInfo,
+        want: Coin,
+        offer_denom: String,
+        expiry: u64,
     ) -> Result<Response, ContractError> {
-        deps.api.debug("Initiate confirm of swap order");
+        validate_coins_number(&info.funds, 0)?;
+        validate_different_denoms(&want.denom, &offer_denom)?;
+        validate_native_denom(&want.denom)?;
+        validate_native_denom(&offer_denom)?;
 
-        validate_coins_number(&info.funds, 1)?;
+        let request = QuoteRequest {
+            requester: info.sender.clone(),
+            want,
+            offer_denom,
+            expiry: env.block.time.plus_seconds(expiry).seconds(),
+            quotes: 0,
+            status: OrderStatus::Open,
+        };
+        let request_id = next_quote_request_id(deps.storage)?;
+        QUOTE_REQUESTS.save(deps.storage, request_id, &request)?;
 
-        // Sender is the address of the user that granted this contract the
-        // execution.
-        if maker != info.sender {
-            return Err(ContractError::Unauthorized {});
-        }
+        Ok(Response::new()
+            .add_attribute("action", "create_quote_request")
+            .add_attribute("request_id", request_id.to_string())
+            .add_attribute("requester", info.sender))
+    }
+
+    /// Cancel an open quote request. Submitted quotes stay reserved to the
+    /// requester until their timeout.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not the requester.
+    /// - the request is not open.
+    pub fn cancel_quote_request(
+        deps: DepsMut,
+        info: MessageInfo,
+        request_id: u64,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let mut request = QUOTE_REQUESTS.load(deps.storage, request_id)?;
+        if request.requester != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if request.status != OrderStatus::Open {
+            return Err(ContractError::QuoteRequestNotAvailable {
+                status: request.status.to_string(),
+                expiration: request.expiry,
+            });
+        }
+        request.status = OrderStatus::Deleted;
+        QUOTE_REQUESTS.save(deps.storage, request_id, &request)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "cancel_quote_request")
+            .add_attribute("request_id", request_id.to_string()))
+    }
+
+    /// Quote a request with a swap order of the maker reserved to the requester.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to create orders.
+    /// - the maker is the requester.
+    /// - the request is not open or expired.
+    /// - the request reached `MAX_QUOTES_PER_REQUEST` quotes.
+    /// - any of the errors of `create_order`.
+    pub fn submit_quote(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        request_id: u64,
+        maker: Option<String>,
+        price: Uint128,
+        timeout: u64,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CreateOrders,
+        )?;
+
+        let mut request = QUOTE_REQUESTS.load(deps.storage, request_id)?;
+        if request.requester == maker {
+            return Err(ContractError::Unauthorized {});
+        }
+        validate_quote_request_open(&request, env.block.time.seconds())?;
+        if request.quotes >= MAX_QUOTES_PER_REQUEST {
+            return Err(ContractError::TooManyQuotes {
+                max: MAX_QUOTES_PER_REQUEST,
+            });
+        }
+
+        let order_id = create_order(
+            deps.branch(),
+            &env,
+            &maker,
+            SwapOrderParams {
+                coin_in: request.want.clone(),
+                coin_out: Coin {
+                    denom: request.offer_denom.clone(),
+                    amount: price,
+                },
+                allowed_takers: vec![request.requester.to_string()],
+                taker_merkle_root: None,
+                taker_group: None,
+                mint_receipt: false,
+                reserved_for: None,
+                timeout,
+            },
+            OrderKind::Fixed,
+            Some(request_id),
+        )?;
+        QUOTES.save(deps.storage, (request_id, &maker, order_id), &())?;
+        request.quotes += 1;
+        QUOTE_REQUESTS.save(deps.storage, request_id, &request)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "submit_quote")
+            .add_attribute("request_id", request_id.to_string())
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker))
+    }
+
+    /// Accept a quote of a request. The quote is accepted as a swap order and
+    /// the other open quotes of the request are closed. The request is open
+    /// again if the settlement of the quote fails.
+    ///
+    /// # Errors
+    ///
+    /// - sender is not the requester.
+    /// - the request is not open or expired.
+    /// - the order is not a quote of the request.
+    /// - any of the errors of `accept_swap_order`.
+    pub fn accept_quote(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        request_id: u64,
+        maker: String,
+        order_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut request = QUOTE_REQUESTS.load(deps.storage, request_id)?;
+        if request.requester != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        validate_quote_request_open(&request, env.block.time.seconds())?;
+
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
+        if !QUOTES.has(deps.storage, (request_id, &maker, order_id)) {
+            return Err(ContractError::QuoteNotFound {
+                request_id,
+                order_id,
+            });
+        }
+
+        let quotes = QUOTES
+            .sub_prefix(request_id)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, u64)>>>()?;
+        let mut refunds = vec![];
+        for (quote_maker, quote_id) in quotes {
+            if quote_id == order_id {
+                continue;
+            }
+            // The losing quotes are closed without counting as cancelled by
+            // their makers.
+            let quote = SWAP_ORDERS.load(deps.storage, (&quote_maker, quote_id))?;
+            if matches!(quote.status, OrderStatus::Open | OrderStatus::Reserved) {
+                refunds.extend(close_order(
+                    deps.storage,
+                    &env,
+                    &quote_maker,
+                    quote_id,
+                    quote,
+                )?);
+            }
+        }
+        request.status = OrderStatus::Accepted;
+        QUOTE_REQUESTS.save(deps.storage, request_id, &request)?;
+
+        let response = accept_swap_order(deps, info, env, order_id, maker.to_string(), vec![])?;
+        Ok(response
+            .add_messages(refunds)
+            .add_attribute("request_id", request_id.to_string()))
+    }
+
+    /// Create several atomic swap orders. Each order is validated as in
+    /// `create_swap_order` and the message fails if any of them is not valid.
+    ///
+    /// # Errors
+    ///
+    /// - no order is specified.
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to create orders.
+    /// - any of the errors of `create_order`.
+    pub fn create_swap_orders(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        maker: Option<String>,
+        orders: Vec<SwapOrderParams>,
+    ) -> Result<Response, ContractError> {
+        ensure!(!orders.is_empty(), ContractError::EmptyBatch {});
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CreateOrders,
+        )?;
+
+        let order_ids = orders
+            .into_iter()
+            .map(|params| create_order(deps.branch(), &env, &maker, params, OrderKind::Fixed, None))
+            .collect::<Result<Vec<u64>, ContractError>>()?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "create_swap_orders")
+            .add_attribute("order_ids", join_order_ids(&order_ids))
+            .add_attribute("maker", &maker);
+        for order_id in order_ids {
+            if let Some(msg) = mint_receipt_msg(deps.storage, &maker, order_id)? {
+                response = response.add_message(msg);
+            }
+            if let Some((msgs, event)) = cross_order(deps.branch(), &env, &maker, order_id)? {
+                response = response.add_submessages(msgs).add_event(event);
+            }
+        }
+        Ok(response)
+    }
+
+    /// Stores a new atomic swap order of `maker` and returns its identifier.
+    /// Quotes specify the identifier of the answered quote request.
+    ///
+    /// # Errors
+    ///
+    ///- `coin_in` and `coin_out` are the same.
+    /// - coins to swap are not native.
+    /// - maker didn't grant this contract to send `coin_in` until `timeout`.
+    /// - any of the errors of `validate_order_funding`.
+    /// - a receipt is required but the market has no receipt contract.
+    fn create_order(
+        mut deps: DepsMut,
+        env: &Env,
+        maker: &Addr,
+        params: SwapOrderParams,
+        kind: OrderKind,
+        quote_request: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        let SwapOrderParams {
+            coin_in,
+            coin_out,
+            allowed_takers,
+            taker_merkle_root,
+            taker_group,
+            mint_receipt,
+            reserved_for,
+            timeout,
+        } = params;
+        validate_different_denoms(&coin_in.denom, &coin_out.denom)?;
+        validate_native_denom(&coin_in.denom)?;
+        validate_native_denom(&coin_out.denom)?;
+
+        if allowed_takers.len() > MAX_ALLOWED_TAKERS {
+            return Err(ContractError::TooManyAllowedTakers {
+                max: MAX_ALLOWED_TAKERS,
+            });
+        }
+        let allowed_takers = allowed_takers
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect::<StdResult<Vec<Addr>>>()?;
+        if let Some(root) = &taker_merkle_root {
+            if root.len() != 32 {
+                return Err(ContractError::InvalidMerkleRoot {
+                    length: root.len() as u64,
+                });
+            }
+        }
+        let taker_group = taker_group
+            .map(|params| validate_taker_group(deps.as_ref(), params))
+            .transpose()?;
+
+        if let Some(reserved_for) = reserved_for {
+            ensure!(
+                !allowed_takers.is_empty() || taker_merkle_root.is_some() || taker_group.is_some(),
+                ContractError::NoDesignatedTakers {}
+            );
+            ensure!(
+                reserved_for > 0 && reserved_for < timeout,
+                ContractError::InvalidReservedFor { timeout }
+            );
+        }
+        let reserved_until =
+            reserved_for.map(|duration| env.block.time.plus_seconds(duration).seconds());
+        let timeout = env.block.time.plus_seconds(timeout).seconds();
+        validate_order_funding(deps.branch(), env, maker, &coin_in, timeout)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if let Some(rule) = &config.suspension_rule {
+            let stats = MAKER_STATS
+                .may_load(deps.storage, maker)?
+                .unwrap_or_default();
+            ensure!(
+                !stats.is_suspended(rule, env.block.time.seconds()),
+                ContractError::MakerSuspended {}
+            );
+        }
+        validate_compliance(
+            &deps.querier,
+            config.compliance_hook.as_ref(),
+            maker,
+            ComplianceAction::CreateSwapOrder,
+        )?;
+        let receipt = if mint_receipt {
+            let receipt_contract = config
+                .receipt_contract
+                .ok_or(ContractError::ReceiptsNotEnabled {})?;
+            Some(receipt_contract)
+        } else {
+            None
+        };
+
+        let swap_order = SwapOrder {
+            coin_in,
+            coin_out,
+            allowed_takers,
+            taker_merkle_root,
+            taker_group,
+            taker: None,
+            timeout,
+            status: OrderStatus::Open,
+            failure_reason: None,
+            kind,
+            receipt,
+            reservation: None,
+            reserved_until,
+            quote_request,
+        };
+
+        let order_id: u64 = next_id(deps.storage)?;
+        SWAP_ORDERS.save(deps.storage, (maker, order_id), &swap_order)?;
+        commit_exposure(deps.storage, maker, order_id, &swap_order)?;
+        update_maker_stats(deps.storage, maker, |stats| stats.created += 1)?;
+        add_to_order_book(deps.storage, maker, order_id, &swap_order)?;
+
+        Ok(order_id)
+    }
+
+    /// Returns the message minting the receipt of the order to its maker, if
+    /// the order has a receipt. The token identifier is the order identifier.
+    fn mint_receipt_msg(
+        storage: &dyn Storage,
+        maker: &Addr,
+        order_id: u64,
+    ) -> StdResult<Option<WasmMsg>> {
+        let order = SWAP_ORDERS.load(storage, (maker, order_id))?;
+        let Some(receipt_contract) = order.receipt else {
+            return Ok(None);
+        };
+
+        let mint_msg = Cw721ExecuteMsg::<Extension, Empty>::Mint {
+            token_id: order_id.to_string(),
+            owner: maker.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        Ok(Some(WasmMsg::Execute {
+            contract_addr: receipt_contract.into_string(),
+            msg: to_json_binary(&mint_msg)?,
+            funds: vec![],
+        }))
+    }
+
+    /// Returns the address receiving the proceeds of an order: the holder of
+    /// its receipt or, if the order has no receipt, the maker.
+    ///
+    /// # Errors
+    ///
+    /// - the receipt contract can't be queried or the receipt has been burned.
+    fn proceeds_recipient(
+        deps: Deps,
+        maker: &Addr,
+        order_id: u64,
+        order: &SwapOrder,
+    ) -> StdResult<Addr> {
+        let Some(receipt_contract) = &order.receipt else {
+            return Ok(maker.clone());
+        };
+
+        let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+            receipt_contract,
+            &Cw721QueryMsg::<Empty>::OwnerOf {
+                token_id: order_id.to_string(),
+                include_expired: None,
+            },
+        )?;
+        Ok(Addr::unchecked(res.owner))
+    }
+
+    /// Checks that the maker still holds the receipt of the order, if any. Once
+    /// the receipt is transferred the order is handed off, so it can't be
+    /// amended or cancelled anymore.
+    fn validate_receipt_held(
+        deps: Deps,
+        maker: &Addr,
+        order_id: u64,
+        order: &SwapOrder,
+    ) -> Result<(), ContractError> {
+        let holder = proceeds_recipient(deps, maker, order_id, order)?;
+        ensure!(
+            holder == maker,
+            ContractError::ReceiptTransferred {
+                holder: holder.into_string()
+            }
+        );
+        Ok(())
+    }
+
+    /// Checks that `coin_in`, along with the other open orders of the maker
+    /// selling the same denom, can be settled through `x/authz` until `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// - maker didn't grant this contract to send `coin_in` until `timeout`.
+    /// - `coin_in` and the open orders of the maker exceed the grant spend limit
+    ///   or, if required by the config, the maker balance.
+    fn validate_order_funding(
+        deps: DepsMut,
+        env: &Env,
+        maker: &Addr,
+        coin_in: &Coin,
+        timeout: u64,
+    ) -> Result<(), ContractError> {
+        release_expired_exposure(deps.storage, maker, env.block.time.seconds())?;
+        let required = MAKER_EXPOSURE
+            .may_load(deps.storage, (maker, &coin_in.denom))?
+            .unwrap_or_default()
+            .checked_add(coin_in.amount)?;
+
+        let spend_limit = query_authz_spend_limit(
+            &deps.querier,
+            &env.contract.address,
+            maker,
+            &coin_in.denom,
+            timeout,
+        )?;
+        if spend_limit < required {
+            return Err(ContractError::GrantLimitExceeded {
+                denom: coin_in.denom.clone(),
+                limit: spend_limit.u128(),
+                required: required.u128(),
+            });
+        }
+
+        if CONFIG.load(deps.storage)?.check_maker_balance {
+            validate_maker_balance(
+                deps.as_ref(),
+                maker,
+                &Coin::new(required.u128(), &coin_in.denom),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Settles the order of `maker` with the cheapest open order crossing it, the
+    /// oldest one among orders with the same price. Orders no longer open are
+    /// removed from the order book, while reserved orders and the orders of the
+    /// same maker are skipped. At most `MAX_CROSSING_CANDIDATES` settlements are
+    /// attempted.
+    fn cross_order(
+        mut deps: DepsMut,
+        env: &Env,
+        maker: &Addr,
+        order_id: u64,
+    ) -> Result<Option<(Vec<SubMsg>, Event)>, ContractError> {
+        let order = SWAP_ORDERS.load(deps.storage, (maker, order_id))?;
+        if order.kind != OrderKind::Fixed || order.quote_request.is_some() {
+            return Ok(None);
+        }
+        let block_time = env.block.time.seconds();
+
+        // Only the orders selling exactly the `coin_out` of the order for at
+        // most its `coin_in` can cross it.
+        let book = ORDER_BOOK
+            .prefix((
+                (&order.coin_out.denom, &order.coin_in.denom),
+                order.coin_out.amount.u128(),
+            ))
+            .range(
+                deps.storage,
+                None,
+                Some(Bound::inclusive((order.coin_in.amount.u128(), u64::MAX))),
+                Order::Ascending,
+            );
+        let mut closed_orders = vec![];
+        let mut candidates = vec![];
+        for entry in book {
+            let ((_, resting_id), resting_maker) = entry?;
+            let resting_order = SWAP_ORDERS.load(deps.storage, (&resting_maker, resting_id))?;
+            match resting_order.current_status(block_time) {
+                OrderStatus::Open if resting_order.timeout >= block_time => {}
+                OrderStatus::Reserved => continue,
+                _ => {
+                    closed_orders.push((resting_id, resting_order));
+                    continue;
+                }
+            }
+            if resting_maker == *maker {
+                continue;
+            }
+            candidates.push((resting_maker, resting_id));
+            if candidates.len() == MAX_CROSSING_CANDIDATES {
+                break;
+            }
+        }
+        for (resting_id, resting_order) in closed_orders {
+            remove_from_order_book(deps.storage, resting_id, &resting_order);
+        }
+
+        for (resting_maker, resting_id) in candidates {
+            // Orders that cannot be settled are skipped since checks are done
+            // before any state change.
+            if let Ok(settlement) = settle_crossing_orders(
+                deps.branch(),
+                env,
+                (resting_maker, resting_id, &[]),
+                (maker.clone(), order_id, &[]),
+            ) {
+                return Ok(Some(settlement));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Settles two crossing orders of different makers. The order created first is
+    /// considered the resting one.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - any of the errors of `settle_crossing_orders`.
+    pub fn match_orders(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order: (String, u64),
+        counter_order: (String, u64),
+        proof: Vec<HexBinary>,
+        counter_proof: Vec<HexBinary>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        // We don't care about validation because the addresses are used to match a key.
+        let order = (Addr::unchecked(order.0), order.1, proof.as_slice());
+        let counter_order = (
+            Addr::unchecked(counter_order.0),
+            counter_order.1,
+            counter_proof.as_slice(),
+        );
+        let (resting, incoming) = if order.1 < counter_order.1 {
+            (order, counter_order)
+        } else {
+            (counter_order, order)
+        };
+
+        let (msgs, event) = settle_crossing_orders(deps, &env, resting, incoming)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "match_orders")
+            .add_submessages(msgs)
+            .add_event(event))
+    }
+
+    /// Accepts the `resting` and the `incoming` orders one with the other and
+    /// returns the submessages settling each of them through a `MsgExec`.
+    /// The incoming order is executed at its own terms, while the resting order
+    /// receives the whole `coin_in` of the incoming one.
+    ///
+    /// # Errors
+    ///
+    /// - the orders have the same maker.
+    /// - any of the orders is not open, expired, or reserved to another taker.
+    /// - the incoming order doesn't buy exactly the `coin_in` of the resting order
+    ///   paying at least its `coin_out`.
+    /// - any of the makers has not enough funds to settle its order.
+    ///
+    /// Each order comes with the Merkle proof of its maker for the opposite order.
+    fn settle_crossing_orders(
+        deps: DepsMut,
+        env: &Env,
+        resting: (Addr, u64, &[HexBinary]),
+        incoming: (Addr, u64, &[HexBinary]),
+    ) -> Result<(Vec<SubMsg>, Event), ContractError> {
+        let (resting_maker, resting_id, resting_proof) = resting;
+        let (incoming_maker, incoming_id, incoming_proof) = incoming;
+
+        let mut resting_order = validate_acceptance(
+            deps.as_ref(),
+            env,
+            &incoming_maker,
+            &resting_maker,
+            resting_id,
+            incoming_proof,
+        )?;
+        let mut incoming_order = validate_acceptance(
+            deps.as_ref(),
+            env,
+            &resting_maker,
+            &incoming_maker,
+            incoming_id,
+            resting_proof,
+        )?;
+
+        // A reserved order can only be accepted by its taker, not crossed.
+        for order in [&resting_order, &incoming_order] {
+            validate_not_reserved(order, env.block.time.seconds())?;
+        }
+
+        if incoming_order.coin_out != resting_order.coin_in
+            || incoming_order.coin_in.denom != resting_order.coin_out.denom
+            || incoming_order.coin_in.amount < resting_order.coin_out.amount
+        {
+            return Err(ContractError::OrdersNotCrossing {
+                order_id: incoming_id,
+                counter_order_id: resting_id,
+            });
+        }
+
+        validate_maker_balance(deps.as_ref(), &resting_maker, &resting_order.coin_in)?;
+        validate_maker_balance(deps.as_ref(), &incoming_maker, &incoming_order.coin_in)?;
+
+        // Reservations are ended, so their deposits are added to the maker bonds.
+        let block_time = env.block.time.seconds();
+        release_reservation(deps.storage, &resting_maker, &mut resting_order, block_time)?;
+        release_reservation(
+            deps.storage,
+            &incoming_maker,
+            &mut incoming_order,
+            block_time,
+        )?;
+
+        resting_order.taker = Some(incoming_maker.clone());
+        resting_order.status = OrderStatus::Accepted;
+        SWAP_ORDERS.save(deps.storage, (&resting_maker, resting_id), &resting_order)?;
+        MATCHED_ORDERS.save(
+            deps.storage,
+            (&resting_maker, resting_id),
+            &(incoming_maker.clone(), incoming_id),
+        )?;
+
+        incoming_order.taker = Some(resting_maker.clone());
+        incoming_order.status = OrderStatus::Accepted;
+        SWAP_ORDERS.save(
+            deps.storage,
+            (&incoming_maker, incoming_id),
+            &incoming_order,
+        )?;
+        MATCHED_ORDERS.save(
+            deps.storage,
+            (&incoming_maker, incoming_id),
+            &(resting_maker.clone(), resting_id),
+        )?;
+
+        let reply_id = next_reply_id(deps.storage)?;
+        PENDING_MATCHES.save(
+            deps.storage,
+            reply_id,
+            &vec![
+                OrderPointer {
+                    order_id: resting_id,
+                    maker: resting_maker.clone(),
+                    taker: incoming_maker.clone(),
+                },
+                OrderPointer {
+                    order_id: incoming_id,
+                    maker: incoming_maker.clone(),
+                    taker: resting_maker.clone(),
+                },
+            ],
+        )?;
+
+        // Each maker confirms its order through its own `MsgExec`. The payouts are
+        // sent when the incoming order is confirmed.
+        let authz_msg =
+            |maker: &Addr, order_id: u64, coin_in: Coin| -> Result<CosmosMsg, ContractError> {
+                let msg_exec = create_authz_encoded_message(
+                    env.contract.address.to_string(),
+                    maker.to_string(),
+                    vec![(order_id, coin_in)],
+                )?;
+                Ok(CosmosMsg::Stargate {
+                    type_url: MsgExec::TYPE_URL.to_string(),
+                    value: msg_exec.into(),
+                })
+            };
+        let resting_msg = authz_msg(&resting_maker, resting_id, resting_order.coin_in)?;
+        let incoming_msg = authz_msg(&incoming_maker, incoming_id, incoming_order.coin_in)?;
+
+        let event = Event::new("swap_orders_matched")
+            .add_attribute("resting_order_id", resting_id.to_string())
+            .add_attribute("resting_maker", resting_maker)
+            .add_attribute("incoming_order_id", incoming_id.to_string())
+            .add_attribute("incoming_maker", incoming_maker);
+
+        // The incoming order settlement replies always to remove the settlement
+        // context also on success, and it is executed also if the resting one
+        // fails.
+        let msgs = vec![
+            SubMsg::reply_on_error(resting_msg, reply_id),
+            SubMsg::reply_always(incoming_msg, reply_id),
+        ];
+        Ok((msgs, event))
+    }
+
+    /// Amend the price or the timeout of an open fixed price order. The amended
+    /// order is crossed with the order book as a new one.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to amend orders.
+    /// - the order doesn't exist, is not open or timed out.
+    /// - the order is not a fixed price order.
+    /// - the receipt of the order has been transferred or can't be queried.
+    /// - any of the errors of `validate_order_funding` for the new timeout.
+    #[allow(clippy::too_many_arguments)]
+    pub fn amend_swap_order(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        order_id: u64,
+        maker: Option<String>,
+        price: Option<Uint128>,
+        timeout: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::AmendOrders,
+        )?;
+
+        let mut order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+        validate_status_and_expiration(&order, OrderStatus::Open, env.block.time.seconds())?;
+        ensure!(
+            order.kind == OrderKind::Fixed,
+            ContractError::OrderNotAmendable {}
+        );
+        validate_receipt_held(deps.as_ref(), &maker, order_id, &order)?;
+
+        release_reservation(deps.storage, &maker, &mut order, env.block.time.seconds())?;
+
+        // The order exposure is committed again under the new timeout.
+        release_exposure(deps.storage, &maker, order_id, &order)?;
+        remove_from_order_book(deps.storage, order_id, &order);
+        if let Some(price) = price {
+            order.coin_out.amount = price;
+        }
+        if let Some(timeout) = timeout {
+            order.timeout = env.block.time.plus_seconds(timeout).seconds();
+        }
+        validate_order_funding(deps.branch(), &env, &maker, &order.coin_in, order.timeout)?;
+        SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+        commit_exposure(deps.storage, &maker, order_id, &order)?;
+        add_to_order_book(deps.storage, &maker, order_id, &order)?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "amend_swap_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", &maker)
+            .add_attribute("coin_out", order.coin_out.to_string())
+            .add_attribute("timeout", order.timeout.to_string());
+        if let Some((msgs, event)) = cross_order(deps, &env, &maker, order_id)? {
+            response = response.add_submessages(msgs).add_event(event);
+        }
+        Ok(response)
+    }
+
+    /// Cancel open swap orders of `maker`, or of the sender if not specified.
+    ///
+    /// # Errors
+    ///
+    /// - no order is specified.
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to cancel orders.
+    /// - any of the orders doesn't exist or is not open.
+    /// - the receipt of any of the orders has been transferred or can't be
+    ///   queried.
+    pub fn cancel_swap_orders(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        maker: Option<String>,
+        order_ids: Vec<u64>,
+    ) -> Result<Response, ContractError> {
+        ensure!(!order_ids.is_empty(), ContractError::EmptyBatch {});
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CancelOrders,
+        )?;
+
+        let mut refunds = vec![];
+        for order_id in &order_ids {
+            let order = SWAP_ORDERS.load(deps.storage, (&maker, *order_id))?;
+            let status = order.current_status(env.block.time.seconds());
+            if status != OrderStatus::Open {
+                return Err(ContractError::SwapOrderNotAvailable {
+                    status: status.to_string(),
+                    expiration: order.timeout,
+                });
+            }
+            ensure!(!order.has_bids(), ContractError::AuctionHasBids {});
+            validate_receipt_held(deps.as_ref(), &maker, *order_id, &order)?;
+            refunds.extend(cancel_order(deps.storage, &env, &maker, *order_id, order)?);
+        }
+
+        Ok(Response::new()
+            .add_messages(refunds)
+            .add_attribute("action", "cancel_swap_orders")
+            .add_attribute("order_ids", join_order_ids(&order_ids))
+            .add_attribute("maker", maker))
+    }
+
+    /// Cancel all the open swap orders of the maker. If `denom_pair` is specified,
+    /// only the orders swapping these two denoms, in any direction, are cancelled.
+    /// Auctions with bids, reserved orders and orders whose receipt has been
+    /// transferred are not cancelled.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - sender is not an operator of `maker` allowed to cancel orders.
+    /// - the receipt of any of the orders can't be queried.
+    pub fn cancel_all_my_orders(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        maker: Option<String>,
+        denom_pair: Option<(String, String)>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = resolve_maker(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            maker,
+            OperatorPermission::CancelOrders,
+        )?;
+
+        let orders = SWAP_ORDERS
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| match item {
+                Ok((_, order)) => {
+                    order.current_status(env.block.time.seconds()) == OrderStatus::Open
+                        && !order.has_bids()
+                        && denom_pair.as_ref().is_none_or(|(first, second)| {
+                            let denoms = (&order.coin_in.denom, &order.coin_out.denom);
+                            denoms == (first, second) || denoms == (second, first)
+                        })
+                }
+                Err(_) => true,
+            })
+            .collect::<StdResult<Vec<(u64, SwapOrder)>>>()?;
+
+        let mut order_ids = vec![];
+        let mut refunds = vec![];
+        for (order_id, order) in orders {
+            if proceeds_recipient(deps.as_ref(), &maker, order_id, &order)? != maker {
+                continue;
+            }
+            refunds.extend(cancel_order(deps.storage, &env, &maker, order_id, order)?);
+            order_ids.push(order_id);
+        }
+
+        Ok(Response::new()
+            .add_messages(refunds)
+            .add_attribute("action", "cancel_all_my_orders")
+            .add_attribute("order_ids", join_order_ids(&order_ids))
+            .add_attribute("maker", maker))
+    }
+
+    /// Cancels an order of the maker, counting it in the maker stats. Returns
+    /// the messages of `close_order`.
+    fn cancel_order(
+        storage: &mut dyn Storage,
+        env: &Env,
+        maker: &Addr,
+        order_id: u64,
+        order: SwapOrder,
+    ) -> StdResult<Vec<BankMsg>> {
+        update_maker_stats(storage, maker, |stats| stats.cancelled += 1)?;
+        close_order(storage, env, maker, order_id, order)
+    }
+
+    /// Marks an order as deleted and releases its exposure. Returns the messages
+    /// to refund the counter-offers and the reservation deposit of the order.
+    fn close_order(
+        storage: &mut dyn Storage,
+        env: &Env,
+        maker: &Addr,
+        order_id: u64,
+        mut order: SwapOrder,
+    ) -> StdResult<Vec<BankMsg>> {
+        let mut refunds = vec![];
+        refunds.extend(release_reservation(
+            storage,
+            maker,
+            &mut order,
+            env.block.time.seconds(),
+        )?);
+        order.status = OrderStatus::Deleted;
+        SWAP_ORDERS.save(storage, (maker, order_id), &order)?;
+        remove_from_order_book(storage, order_id, &order);
+        release_exposure(storage, maker, order_id, &order)?;
+        refunds.extend(refund_counter_offers(storage, maker, order_id, |_| true)?);
+        Ok(refunds)
+    }
+
+    fn join_order_ids(order_ids: &[u64]) -> String {
+        order_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Reserve an open order for `duration` seconds. The sender escrows the
+    /// deposit of the reservation policy, which is refunded if the sender
+    /// accepts the order before the end of the reservation and added to the
+    /// maker bond otherwise. The order is open again at the end of the
+    /// reservation without any message.
+    ///
+    /// # Errors
+    ///
+    /// - the market has no reservation policy.
+    /// - sent coins are not the deposit of the reservation policy.
+    /// - `duration` is zero or longer than the maximum of the policy.
+    /// - the order is already reserved.
+    /// - any of the errors of `validate_acceptance`.
+    pub fn reserve_swap_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+        duration: u64,
+        proof: Vec<HexBinary>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 1)?;
+        let policy = CONFIG
+            .load(deps.storage)?
+            .reservation_policy
+            .ok_or(ContractError::ReservationsNotEnabled {})?;
+        check_correct_coins(&info.funds[0], &policy.deposit)?;
+        if duration == 0 || duration > policy.max_duration {
+            return Err(ContractError::InvalidReservationDuration {
+                max: policy.max_duration,
+            });
+        }
+
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
+        let block_time = env.block.time.seconds();
+        let order =
+            validate_acceptance(deps.as_ref(), &env, &info.sender, &maker, order_id, &proof)?;
+        validate_not_reserved(&order, block_time)?;
+
+        // The order is saved without the current price of `validate_acceptance`.
+        let mut order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+        release_reservation(deps.storage, &maker, &mut order, block_time)?;
+        let expiration = env.block.time.plus_seconds(duration).seconds();
+        order.status = OrderStatus::Reserved;
+        order.reservation = Some(Reservation {
+            taker: info.sender.clone(),
+            deposit: policy.deposit,
+            expiration,
+        });
+        SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "reserve_swap_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker)
+            .add_attribute("taker", info.sender)
+            .add_attribute("reserved_until", expiration.to_string()))
+    }
+
+    // Accept a swap order.
+    //
+    // # Errors
+    //
+    // - more than one coin is sent to the contract.
+    // - sender is equal to matching order maker.
+    // - selected order is not open or timed out.
+    // - sent coin doesn't match maker wanted coin.
+    // - sender is not one of the allowed takers if specified.
+    // - maker doesn't have enough funds to settle the order.
+    pub fn accept_swap_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+        proof: Vec<HexBinary>,
+    ) -> Result<Response, ContractError> {
+        deps.api.debug("Initiate acceptance of swap order");
+
+        validate_coins_number(&info.funds, 1)?;
+
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
+        let mut order =
+            validate_acceptance(deps.as_ref(), &env, &info.sender, &maker, order_id, &proof)?;
+        let payment = &info.funds[0];
+        if let OrderKind::Dutch { .. } = order.kind {
+            check_sufficient_coins(payment, &order.coin_out)?;
+        } else {
+            check_correct_coins(payment, &order.coin_out)?;
+        }
+        let overpayment = payment.amount - order.coin_out.amount;
+
+        // Check that the maker can settle the order before sending the `MsgExec`
+        // to avoid wasting the taker gas in a failing settlement.
+        validate_maker_balance(deps.as_ref(), &maker, &order.coin_in)?;
+
+        let deposit_refund =
+            release_reservation(deps.storage, &maker, &mut order, env.block.time.seconds())?;
+        order.taker = Some(info.sender.clone());
+        order.status = OrderStatus::Accepted;
+
+        SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+
+        // Save the context of the settlement under a unique reply identifier
+        // to restore the order if the settlement fails.
+        let reply_id = next_reply_id(deps.storage)?;
+        PENDING_SETTLEMENTS.save(
+            deps.storage,
+            reply_id,
+            &vec![OrderPointer {
+                maker: maker.clone(),
+                taker: info.sender.clone(),
+                order_id,
+            }],
+        )?;
+
+        // Create encoded `x/authz` message to trigger `ConfirmSwapOrder`
+        // on behalf of the order maker.
+        let msg_exec = create_authz_encoded_message(
+            env.contract.address.to_string(),
+            maker.to_string(),
+            vec![(order_id, order.coin_in)],
+        )?;
+        let authz_msg: CosmosMsg = CosmosMsg::Stargate {
+            type_url: MsgExec::TYPE_URL.to_string(),
+            value: msg_exec.into(),
+        };
+        // Reply always to remove the settlement context also on success.
+        let msg = SubMsg::reply_always(authz_msg, reply_id);
+
+        let mut response = Response::new()
+            .add_attribute("action", "accept_swap_order")
+            .add_attribute("order_taker", order.taker.unwrap())
+            .add_submessage(msg)
+            .add_messages(deposit_refund);
+        if !overpayment.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: payment.denom.clone(),
+                    amount: overpayment,
+                }],
+            });
+        }
+        Ok(response)
+    }
+
+    // Accept several swap orders with the sum of the required funds. A single
+    // `MsgExec` is sent for each maker to confirm all its accepted orders.
+    //
+    // With `AllOrNothing` the message fails if any of the orders cannot be
+    // accepted or settled. With `BestEffort` the orders that cannot be accepted
+    // are skipped and the settlement failures are handled in the reply. Funds not
+    // used by the accepted orders are refunded to the sender. The result of each
+    // order is returned in the response data.
+    //
+    // # Errors
+    //
+    // - no order is specified.
+    // - with `AllOrNothing`, any of the errors of `AcceptSwapOrder` or the sent
+    //   funds don't cover the orders.
+    pub fn accept_swap_orders(
+        mut deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        orders: Vec<(String, u64)>,
+        mode: AcceptMode,
+        proofs: Vec<Vec<HexBinary>>,
+    ) -> Result<Response, ContractError> {
+        deps.api.debug("Initiate acceptance of swap orders");
+
+        ensure!(!orders.is_empty(), ContractError::EmptyBatch {});
+
+        let mut remaining_funds = info.funds.clone();
+        let mut committed = BTreeMap::new();
+        let mut settlements: BTreeMap<Addr, Vec<(OrderPointer, Coin)>> = BTreeMap::new();
+        let mut results = vec![];
+        for (i, (maker, order_id)) in orders.into_iter().enumerate() {
+            let maker = Addr::unchecked(maker);
+            let proof = proofs.get(i).map(Vec::as_slice).unwrap_or_default();
+            let result = accept_batched_order(
+                deps.branch(),
+                &env,
+                (&info.sender, proof),
+                &maker,
+                order_id,
+                &mut remaining_funds,
+                &mut committed,
+            );
+            let error = match result {
+                Ok(settlement) => {
+                    settlements
+                        .entry(maker.clone())
+                        .or_default()
+                        .push(settlement);
+                    None
+                }
+                Err(err) if mode == AcceptMode::BestEffort => Some(err.to_string()),
+                Err(err) => return Err(err),
+            };
+            results.push(AcceptSwapOrderResult {
+                maker: maker.to_string(),
+                order_id,
+                error,
+            });
+        }
+
+        let mut response = Response::new()
+            .add_attribute("action", "accept_swap_orders")
+            .add_attribute("order_taker", info.sender.as_str())
+            .add_attribute(
+                "accepted",
+                results
+                    .iter()
+                    .filter(|result| result.error.is_none())
+                    .count()
+                    .to_string(),
+            );
+
+        for (maker, settlement) in settlements {
+            let (order_pointers, orders): (Vec<_>, Vec<_>) = settlement
+                .into_iter()
+                .map(|(pointer, coin_in)| {
+                    let order_id = pointer.order_id;
+                    (pointer, (order_id, coin_in))
+                })
+                .unzip();
+            let msg_exec = create_authz_encoded_message(
+                env.contract.address.to_string(),
+                maker.to_string(),
+                orders,
+            )?;
+            let authz_msg: CosmosMsg = CosmosMsg::Stargate {
+                type_url: MsgExec::TYPE_URL.to_string(),
+                value: msg_exec.into(),
+            };
+
+            let msg = match mode {
+                // The failure of a settlement reverts the whole transaction.
+                AcceptMode::AllOrNothing => SubMsg::new(authz_msg),
+                AcceptMode::BestEffort => {
+                    let reply_id = next_reply_id(deps.storage)?;
+                    PENDING_SETTLEMENTS.save(deps.storage, reply_id, &order_pointers)?;
+                    SubMsg::reply_always(authz_msg, reply_id)
+                }
+            };
+            response = response.add_submessage(msg);
+        }
+
+        remaining_funds.retain(|coin| !coin.amount.is_zero());
+        if !remaining_funds.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: remaining_funds,
+            });
+        }
+
+        Ok(response.set_data(to_json_binary(&AcceptSwapOrdersResponse { results })?))
+    }
+
+    /// Accepts an order of a batch using the remaining funds sent by the taker.
+    /// `committed` tracks the amount of each denom that makers have to send to
+    /// settle the orders already accepted in the batch.
+    fn accept_batched_order(
+        deps: DepsMut,
+        env: &Env,
+        (taker, proof): (&Addr, &[HexBinary]),
+        maker: &Addr,
+        order_id: u64,
+        remaining_funds: &mut Vec<Coin>,
+        committed: &mut BTreeMap<(Addr, String), Uint128>,
+    ) -> Result<(OrderPointer, Coin), ContractError> {
+        let mut order = validate_acceptance(deps.as_ref(), env, taker, maker, order_id, proof)?;
+
+        let coin_out = &order.coin_out;
+        let funds = remaining_funds
+            .iter_mut()
+            .find(|coin| coin.denom == coin_out.denom);
+        let available = funds.as_ref().map(|coin| coin.amount).unwrap_or_default();
+        if available < coin_out.amount {
+            return Err(ContractError::InsufficientBatchFunds {
+                denom: coin_out.denom.clone(),
+                available: available.u128(),
+                required: coin_out.amount.u128(),
+            });
+        }
+
+        let key = (maker.clone(), order.coin_in.denom.clone());
+        let required = Coin {
+            denom: order.coin_in.denom.clone(),
+            amount: committed
+                .get(&key)
+                .copied()
+                .unwrap_or_default()
+                .checked_add(order.coin_in.amount)?,
+        };
+        validate_maker_balance(deps.as_ref(), maker, &required)?;
+
+        // Unwrapping is safe because available funds are greater than zero.
+        let funds = funds.unwrap();
+        funds.amount -= coin_out.amount;
+        committed.insert(key, required.amount);
+
+        // The deposit of the taker reservation is refunded with the remaining funds.
+        let deposit_refund =
+            release_reservation(deps.storage, maker, &mut order, env.block.time.seconds())?;
+        if let Some(BankMsg::Send { amount, .. }) = deposit_refund {
+            for deposit in amount {
+                match remaining_funds
+                    .iter_mut()
+                    .find(|coin| coin.denom == deposit.denom)
+                {
+                    Some(coin) => coin.amount += deposit.amount,
+                    None => remaining_funds.push(deposit),
+                }
+            }
+        }
+        order.taker = Some(taker.clone());
+        order.status = OrderStatus::Accepted;
+        SWAP_ORDERS.save(deps.storage, (maker, order_id), &order)?;
+
+        Ok((
+            OrderPointer {
+                order_id,
+                maker: maker.clone(),
+                taker: taker.clone(),
+            },
+            order.coin_in,
+        ))
+    }
+
+    /// Checks that `taker` can accept the order and returns it with `coin_out`
+    /// set to the current price. The `proof` is required only by orders with a
+    /// merkle root when the taker is not one of the `allowed_takers`. Quotes
+    /// can be accepted only once `accept_quote` marked their request accepted.
+    fn validate_acceptance(
+        deps: Deps,
+        env: &Env,
+        taker: &Addr,
+        maker: &Addr,
+        order_id: u64,
+        proof: &[HexBinary],
+    ) -> Result<SwapOrder, ContractError> {
+        validate_taker(deps, maker, taker)?;
+
+        let mut order = SWAP_ORDERS.load(deps.storage, (maker, order_id))?;
+        // During the reservation the order can be accepted only by its taker.
+        let block_time = env.block.time.seconds();
+        let reserved_by_taker = order
+            .reservation
+            .as_ref()
+            .is_some_and(|reservation| reservation.taker == taker);
+        let valid_status = match order.current_status(block_time) {
+            OrderStatus::Reserved if reserved_by_taker => OrderStatus::Reserved,
+            _ => {
+                validate_not_reserved(&order, block_time)?;
+                OrderStatus::Open
+            }
+        };
+        validate_status_and_expiration(&order, valid_status, block_time)?;
+        if let OrderKind::English { .. } = order.kind {
+            return Err(ContractError::AuctionOrder {});
+        }
+        // Quotes are accepted only through `accept_quote`, which marks their
+        // request as accepted once the other quotes are closed.
+        if let Some(request_id) = order.quote_request {
+            let request = QUOTE_REQUESTS.load(deps.storage, request_id)?;
+            ensure!(
+                request.status == OrderStatus::Accepted,
+                ContractError::QuoteOrder { request_id }
+            );
+        }
+        order.coin_out = current_price(&order, env.block.time.seconds());
+
+        // Check if the order is private and the sender is not one of the lucky ones.
+        let is_private = order.current_phase(block_time) == OrderPhase::Private;
+        if is_private && !order.allowed_takers.contains(taker) {
+            if let Some(root) = &order.taker_merkle_root {
+                if !verify_proof(root, taker.as_str(), proof) {
+                    ensure!(proof.is_empty(), ContractError::InvalidMerkleProof {});
+                    return Err(ContractError::Unauthorized {});
+                }
+            } else if !order.allowed_takers.is_empty() {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+
+        if let Some(taker_group) = order.taker_group.as_ref().filter(|_| is_private) {
+            validate_group_member(&deps.querier, taker_group, taker)?;
+        }
+
+        Ok(order)
+    }
+
+    /// Checks that `taker` can take an order of `maker`: the maker didn't block
+    /// it, it is a member of the market taker group, if any, and the compliance
+    /// hook allows it.
+    fn validate_taker(deps: Deps, maker: &Addr, taker: &Addr) -> Result<(), ContractError> {
+        if taker == maker {
+            return Err(ContractError::SenderIsMaker {});
+        }
+        validate_not_blocked(deps.storage, maker, taker)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if let Some(taker_group) = &config.taker_group {
+            validate_group_member(&deps.querier, taker_group, taker)?;
+        }
+        validate_compliance(
+            &deps.querier,
+            config.compliance_hook.as_ref(),
+            taker,
+            ComplianceAction::AcceptSwapOrder,
+        )
+    }
+
+    /// Validates the address of a cw4 group referenced by the market or an order.
+    fn validate_taker_group(
+        deps: Deps,
+        params: TakerGroupParams,
+    ) -> Result<TakerGroup, ContractError> {
+        Ok(TakerGroup {
+            group: deps.api.addr_validate(&params.group)?,
+            min_weight: params.min_weight,
+        })
+    }
+
+    /// Returns the maker on whose behalf the sender acts. If `maker` is
+    /// specified and is not the sender, the sender has to be an operator of
+    /// the maker with an unexpired approval for `permission`.
+    fn resolve_maker(
+        deps: Deps,
+        env: &Env,
+        sender: &Addr,
+        maker: Option<String>,
+        permission: OperatorPermission,
+    ) -> Result<Addr, ContractError> {
+        let maker = match maker {
+            Some(maker) if maker != sender.as_str() => deps.api.addr_validate(&maker)?,
+            _ => return Ok(sender.clone()),
+        };
+
+        let approval = OPERATORS
+            .may_load(deps.storage, (&maker, sender))?
+            .filter(|approval| approval.permissions.contains(&permission))
+            .ok_or_else(|| ContractError::OperatorNotApproved {
+                operator: sender.to_string(),
+                maker: maker.to_string(),
+                permission: permission.to_string(),
+            })?;
+        if let Some(expiration) = approval.expires {
+            if expiration <= env.block.time.seconds() {
+                return Err(ContractError::OperatorApprovalExpired { expiration });
+            }
+        }
+        Ok(maker)
+    }
+
+    /// Checks that the order is not reserved at the current block time.
+    fn validate_not_reserved(order: &SwapOrder, block_time: u64) -> Result<(), ContractError> {
+        match &order.reservation {
+            Some(reservation) if order.current_status(block_time) == OrderStatus::Reserved => {
+                Err(ContractError::OrderReserved {
+                    taker: reservation.taker.to_string(),
+                    expiration: reservation.expiration,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the maker didn't block the taker.
+    fn validate_not_blocked(
+        storage: &dyn Storage,
+        maker: &Addr,
+        taker: &Addr,
+    ) -> Result<(), ContractError> {
+        if BLOCKED_TAKERS.has(storage, (maker, taker)) {
+            return Err(ContractError::TakerBlocked {
+                maker: maker.to_string(),
+                taker: taker.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the maker balance covers `required`.
+    fn validate_maker_balance(
+        deps: Deps,
+        maker: &Addr,
+        required: &Coin,
+    ) -> Result<(), ContractError> {
+        let maker_balance = deps.querier.query_balance(maker, &required.denom)?;
+        if maker_balance.amount < required.amount {
+            return Err(ContractError::MakerInsufficientFunds {
+                denom: required.denom.clone(),
+                balance: maker_balance.amount.u128(),
+                required: required.amount.u128(),
+            });
+        }
+        Ok(())
+    }
+
+    /// This function complete the execution of an order between a maker and a taker.
+    /// The logic is executed via `x/authz` after receiving a `MsgExec` from this
+    /// contract.
+    //
+    // # Errors
+    //
+    // - more than one coin is sent to the contract.
+    // - sender is not the maker of the order.
+    // - selected order is not open or timed out.
+    // - sent coin doesn't match maker wanted coin.
+    // - sender is not the specified taker if specified.
+    // - the receipt of the order, or of the crossed order, can't be queried.
+    pub fn confirm_swap_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        deps.api.debug("Initiate confirm of swap order");
+
+        validate_coins_number(&info.funds, 1)?;
+
+        // Sender is the address of the user that granted this contract the
+        // execution.
+        if maker != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
 
         let mut order = SWAP_ORDERS.load(deps.storage, (&info.sender, order_id))?;
 
-        // Return error if the order is expired or already matched.
-        // NOTE: order should not be timeouted since the execution is atomic.
-        if order.status != OrderStatus::Accepted || order.timeout < env.block.time.seconds() {
+        // Return error if the order is expired or already matched. Auctions are
+        // settled after their timeout.
+        // NOTE: order should not be timeouted since the execution is atomic.
+        let expired = order.timeout < env.block.time.seconds()
+            && !matches!(order.kind, OrderKind::English { .. });
+        if order.status != OrderStatus::Accepted || expired {
+            return Err(ContractError::SwapOrderNotAvailable {
+                status: order.status.to_string(),
+                expiration: order.timeout,
+            });
+        }
+
+        // Check if sent coins are the same of the selected order.
+        check_correct_coins(&info.funds[0], &order.coin_in)?;
+
+        order.status = OrderStatus::Confirmed;
+        SWAP_ORDERS.save(deps.storage, (&info.sender, order_id), &order)?;
+        release_exposure(deps.storage, &info.sender, order_id, &order)?;
+        remove_from_order_book(deps.storage, order_id, &order);
+
+        // The proceeds go to the holder of the order receipt, if any, while
+        // `coin_in` has been pulled from the maker through `x/authz`.
+        let recipient = proceeds_recipient(deps.as_ref(), &info.sender, order_id, &order)?;
+        // Unwrapping is save because order is atomic.
+        let mut taker = order.taker.unwrap();
+
+        let refunds = refund_counter_offers(deps.storage, &info.sender, order_id, |_| true)?;
+
+        // The settlement is counted in the maker stats once the payouts are
+        // sent, so when both crossed orders are confirmed.
+        let window = CONFIG
+            .load(deps.storage)?
+            .suspension_rule
+            .map(|rule| rule.window);
+        let record_success = |storage: &mut dyn Storage, maker: &Addr| {
+            update_maker_stats(storage, maker, |stats| {
+                stats.record_settlement(true, env.block.time.seconds(), window)
+            })
+        };
+
+        // An order accepted through a counter-offer receives the offered coin.
+        let mut coin_out = ACCEPTED_OFFERS
+            .may_load(deps.storage, (&info.sender, order_id))?
+            .unwrap_or(order.coin_out);
+        ACCEPTED_OFFERS.remove(deps.storage, (&info.sender, order_id));
+
+        // A crossed order receives the `coin_in` of the opposite order, and the
+        // payouts wait for both orders to be confirmed.
+        if let Some((counter_maker, counter_id)) =
+            MATCHED_ORDERS.may_load(deps.storage, (&info.sender, order_id))?
+        {
+            let counter_order = SWAP_ORDERS.load(deps.storage, (&counter_maker, counter_id))?;
+            if counter_order.status != OrderStatus::Confirmed {
+                return Ok(Response::new()
+                    .add_messages(refunds)
+                    .add_attribute("action", "confirm_swap_order"));
+            }
+            MATCHED_ORDERS.remove(deps.storage, (&info.sender, order_id));
+            MATCHED_ORDERS.remove(deps.storage, (&counter_maker, counter_id));
+            taker = proceeds_recipient(deps.as_ref(), &counter_maker, counter_id, &counter_order)?;
+            coin_out = counter_order.coin_in;
+            record_success(deps.storage, &counter_maker)?;
+        }
+        record_success(deps.storage, &info.sender)?;
+
+        let mut msgs = vec![
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin_out],
+            },
+            BankMsg::Send {
+                to_address: taker.into_string(),
+                amount: vec![order.coin_in],
+            },
+        ];
+        msgs.extend(refunds);
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "confirm_swap_order"))
+    }
+
+    /// Offer a different `coin_out` for an open swap order. The offered coin is
+    /// escrowed and a previous offer of the sender on the order is refunded.
+    ///
+    /// # Errors
+    ///
+    /// - more than one coin is sent to the contract.
+    /// - sender cannot accept the order.
+    /// - the offered coin has not the `coin_out` denom.
+    /// - the order reached `MAX_COUNTER_OFFERS` counter-offers.
+    pub fn make_counter_offer(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+        expiry: u64,
+        proof: Vec<HexBinary>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 1)?;
+
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
+        let order =
+            validate_acceptance(deps.as_ref(), &env, &info.sender, &maker, order_id, &proof)?;
+        let coin = info.funds[0].clone();
+        if coin.denom != order.coin_out.denom {
+            return Err(ContractError::WrongCoin {
+                sent_denom: coin.denom,
+                sent_amount: coin.amount.u128(),
+                expected_denom: order.coin_out.denom,
+                expected_amount: order.coin_out.amount.u128(),
+            });
+        }
+
+        let key = (&maker, order_id, &info.sender);
+        let previous_offer = COUNTER_OFFERS.may_load(deps.storage, key)?;
+        if previous_offer.is_none() {
+            let offers = COUNTER_OFFERS
+                .prefix((&maker, order_id))
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count();
+            if offers >= MAX_COUNTER_OFFERS as usize {
+                return Err(ContractError::TooManyCounterOffers {
+                    max: MAX_COUNTER_OFFERS,
+                });
+            }
+        }
+        COUNTER_OFFERS.save(
+            deps.storage,
+            key,
+            &CounterOffer {
+                coin: coin.clone(),
+                expiry: env.block.time.plus_seconds(expiry).seconds(),
+            },
+        )?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "make_counter_offer")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", &maker)
+            .add_attribute("taker", &info.sender)
+            .add_attribute("offer", coin.to_string());
+        if let Some(previous_offer) = previous_offer {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![previous_offer.coin],
+            });
+        }
+        Ok(response)
+    }
+
+    /// Withdraw the counter-offer of the sender and refund it.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - the sender has no counter-offer on the order.
+    pub fn withdraw_counter_offer(
+        deps: DepsMut,
+        info: MessageInfo,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let maker = Addr::unchecked(maker);
+        let key = (&maker, order_id, &info.sender);
+        let offer = COUNTER_OFFERS.load(deps.storage, key)?;
+        COUNTER_OFFERS.remove(deps.storage, key);
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![offer.coin],
+            })
+            .add_attribute("action", "withdraw_counter_offer")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker))
+    }
+
+    /// Accept a counter-offer on an order of the sender. The order is settled
+    /// through `x/authz` for the offered coin as an accepted order, and the other
+    /// counter-offers are refunded.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - the taker has no counter-offer on the order or it is expired.
+    /// - the taker cannot accept the order anymore.
+    /// - maker has not enough funds to settle the order.
+    pub fn accept_counter_offer(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        taker: String,
+        proof: Vec<HexBinary>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let maker = info.sender;
+        let taker = Addr::unchecked(taker);
+        let offer = COUNTER_OFFERS.load(deps.storage, (&maker, order_id, &taker))?;
+        if offer.expiry < env.block.time.seconds() {
+            return Err(ContractError::CounterOfferExpired {
+                expiration: offer.expiry,
+            });
+        }
+        let mut order = validate_acceptance(deps.as_ref(), &env, &taker, &maker, order_id, &proof)?;
+        validate_maker_balance(deps.as_ref(), &maker, &order.coin_in)?;
+
+        // The accepted offer stays escrowed until the settlement completes.
+        COUNTER_OFFERS.remove(deps.storage, (&maker, order_id, &taker));
+        let mut refunds = refund_counter_offers(deps.storage, &maker, order_id, |_| true)?;
+        refunds.extend(release_reservation(
+            deps.storage,
+            &maker,
+            &mut order,
+            env.block.time.seconds(),
+        )?);
+
+        order.taker = Some(taker.clone());
+        order.status = OrderStatus::Accepted;
+        SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+        ACCEPTED_OFFERS.save(deps.storage, (&maker, order_id), &offer.coin)?;
+
+        let reply_id = next_reply_id(deps.storage)?;
+        PENDING_SETTLEMENTS.save(
+            deps.storage,
+            reply_id,
+            &vec![OrderPointer {
+                order_id,
+                maker: maker.clone(),
+                taker: taker.clone(),
+            }],
+        )?;
+
+        let msg_exec = create_authz_encoded_message(
+            env.contract.address.to_string(),
+            maker.to_string(),
+            vec![(order_id, order.coin_in)],
+        )?;
+        let authz_msg: CosmosMsg = CosmosMsg::Stargate {
+            type_url: MsgExec::TYPE_URL.to_string(),
+            value: msg_exec.into(),
+        };
+
+        Ok(Response::new()
+            .add_submessage(SubMsg::reply_always(authz_msg, reply_id))
+            .add_messages(refunds)
+            .add_attribute("action", "accept_counter_offer")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker)
+            .add_attribute("taker", taker))
+    }
+
+    /// Refund the expired counter-offers of an order, or all of them if the order
+    /// cannot be accepted anymore.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    pub fn refund_expired_counter_offers(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let maker = Addr::unchecked(maker);
+        let block_time = env.block.time.seconds();
+        let order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+        let order_open = matches!(order.status, OrderStatus::Open | OrderStatus::Reserved)
+            && order.timeout >= block_time;
+        let refunds = refund_counter_offers(deps.storage, &maker, order_id, |offer| {
+            !order_open || offer.expiry < block_time
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "refund_counter_offers")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", maker)
+            .add_attribute("refunded", refunds.len().to_string())
+            .add_messages(refunds))
+    }
+
+    /// Create a new hash time-locked order.
+    ///
+    /// # Errors
+    ///
+    /// - `coin_in` and `coin_out` are the same.
+    /// - coins to swap are not native.
+    /// - coins sent to the contract along with the message.
+    /// - `hash_lock` is not a sha256 hash.
+    /// - `time_lock` is zero.
+    /// - the compliance hook rejects the maker.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_htlc_order(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        coin_in: Coin,
+        coin_out: Coin,
+        taker: Option<String>,
+        hash_lock: HexBinary,
+        time_lock: u64,
+        timeout: u64,
+    ) -> Result<Response, ContractError> {
+        validate_different_denoms(&coin_in.denom, &coin_out.denom)?;
+        validate_native_denom(&coin_in.denom)?;
+        validate_native_denom(&coin_out.denom)?;
+        validate_coins_number(&info.funds, 0)?;
+        validate_hash_lock(&hash_lock)?;
+        ensure!(time_lock > 0, ContractError::ZeroTimeLock {});
+        validate_compliance(
+            &deps.querier,
+            CONFIG.load(deps.storage)?.compliance_hook.as_ref(),
+            &info.sender,
+            ComplianceAction::CreateSwapOrder,
+        )?;
+
+        let taker = taker
+            .as_ref()
+            .map(|addr| deps.api.addr_validate(addr))
+            .transpose()?;
+
+        let htlc_order = HtlcOrder {
+            coin_in,
+            coin_out,
+            taker,
+            hash_lock: hash_lock.clone(),
+            time_lock,
+            timeout: env.block.time.plus_seconds(timeout).seconds(),
+            lock_expiration: None,
+            preimage: None,
+            status: OrderStatus::Open,
+        };
+
+        let order_id: u64 = next_id(deps.storage)?;
+        HTLC_ORDERS.save(deps.storage, (&info.sender, order_id), &htlc_order)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "create_htlc_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("maker", info.sender)
+            .add_attribute("hash_lock", hash_lock.to_hex()))
+    }
+
+    // Lock the taker funds into a hash time-locked order.
+    //
+    // # Errors
+    //
+    // - more than one coin is sent to the contract.
+    // - sender is equal to the order maker.
+    // - sender is blocked by the maker, outside the market taker group or
+    //   rejected by the compliance hook.
+    // - selected order is not open or timed out.
+    // - sent coin doesn't match maker wanted coin.
+    // - sender is not the specified taker if specified.
+    pub fn lock_htlc_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 1)?;
+
+        // We don't care about validation because the address is used to match a key.
+        let maker = Addr::unchecked(maker);
+        validate_taker(deps.as_ref(), &maker, &info.sender)?;
+
+        let mut order = HTLC_ORDERS.load(deps.storage, (&maker, order_id))?;
+
+        if order.status != OrderStatus::Open || order.timeout < env.block.time.seconds() {
+            return Err(ContractError::SwapOrderNotAvailable {
+                status: order.status.to_string(),
+                expiration: order.timeout,
+            });
+        }
+        check_correct_coins(&info.funds[0], &order.coin_out)?;
+
+        // Check if the order is reserved and the sender is not the lucky one.
+        if let Some(taker) = order.taker {
+            if taker != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+
+        let lock_expiration = env.block.time.plus_seconds(order.time_lock).seconds();
+        order.taker = Some(info.sender.clone());
+        order.lock_expiration = Some(lock_expiration);
+        order.status = OrderStatus::Accepted;
+
+        HTLC_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "lock_htlc_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("order_taker", info.sender)
+            .add_attribute("lock_expiration", lock_expiration.to_string()))
+    }
+
+    // Claim the locked funds of a hash time-locked order by revealing the
+    // preimage of the hash lock. The sender is the maker and releases
+    // `coin_in` to the taker.
+    //
+    // # Errors
+    //
+    // - more than one coin is sent to the contract.
+    // - selected order is not locked.
+    // - time lock is expired.
+    // - preimage doesn't match the hash lock.
+    // - sent coin doesn't match maker offered coin.
+    pub fn claim_htlc_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        preimage: HexBinary,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 1)?;
+
+        // Orders are stored under the sender address so only the maker
+        // can find its own order.
+        let mut order = HTLC_ORDERS.load(deps.storage, (&info.sender, order_id))?;
+
+        if order.status != OrderStatus::Accepted {
+            return Err(ContractError::SwapOrderNotAvailable {
+                status: order.status.to_string(),
+                expiration: order.timeout,
+            });
+        }
+
+        // Unwrapping is safe because the expiration is set when the order is locked.
+        let lock_expiration = order.lock_expiration.unwrap();
+        if lock_expiration < env.block.time.seconds() {
+            return Err(ContractError::TimeLockExpired {
+                expiration: lock_expiration,
+            });
+        }
+
+        validate_preimage(&preimage, &order.hash_lock)?;
+        check_correct_coins(&info.funds[0], &order.coin_in)?;
+
+        order.status = OrderStatus::Confirmed;
+        order.preimage = Some(preimage.clone());
+        HTLC_ORDERS.save(deps.storage, (&info.sender, order_id), &order)?;
+
+        // Unwrapping is safe because the taker is set when the order is locked.
+        let taker = order.taker.unwrap();
+        let msgs = vec![
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![order.coin_out],
+            },
+            BankMsg::Send {
+                to_address: taker.into_string(),
+                amount: vec![order.coin_in],
+            },
+        ];
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "claim_htlc_order")
+            .add_attribute("order_id", order_id.to_string())
+            .add_attribute("preimage", preimage.to_hex()))
+    }
+
+    // Return the locked funds of a hash time-locked order to the taker.
+    //
+    // # Errors
+    //
+    // - coins sent to the contract along with the message.
+    // - sender is neither the maker nor the taker of the order.
+    // - selected order is not locked.
+    // - time lock is not expired.
+    pub fn refund_htlc_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        order_id: u64,
+        maker: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let maker = Addr::unchecked(maker);
+        let mut order = HTLC_ORDERS.load(deps.storage, (&maker, order_id))?;
+
+        if order.status != OrderStatus::Accepted {
+            return Err(ContractError::SwapOrderNotAvailable {
+                status: order.status.to_string(),
+                expiration: order.timeout,
+            });
+        }
+
+        // Unwrapping is safe because taker and expiration are set when the
+        // order is locked.
+        let taker = order.taker.clone().unwrap();
+        if info.sender != maker && info.sender != taker {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let lock_expiration = order.lock_expiration.unwrap();
+        if lock_expiration >= env.block.time.seconds() {
+            return Err(ContractError::TimeLockNotExpired {
+                expiration: lock_expiration,
+            });
+        }
+
+        order.status = OrderStatus::Refunded;
+        HTLC_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+
+        let refund_msg = BankMsg::Send {
+            to_address: taker.into_string(),
+            amount: vec![order.coin_out],
+        };
+
+        Ok(Response::new()
+            .add_message(refund_msg)
+            .add_attribute("action", "refund_htlc_order")
+            .add_attribute("order_id", order_id.to_string()))
+    }
+
+    // Cancel a hash time-locked order of the sender. No funds are held by
+    // the contract until a taker locks the order.
+    //
+    // # Errors
+    //
+    // - coins sent to the contract along with the message.
+    // - selected order is not open.
+    pub fn cancel_htlc_order(
+        deps: DepsMut,
+        info: MessageInfo,
+        order_id: u64,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        let mut order = HTLC_ORDERS.load(deps.storage, (&info.sender, order_id))?;
+
+        if order.status != OrderStatus::Open {
             return Err(ContractError::SwapOrderNotAvailable {
                 status: order.status.to_string(),
                 expiration: order.timeout,
             });
         }
 
-        // Check if sent coins are the same of the selected order.
-        check_correct_coins(&info.funds[0], &order.coin_in)?;
+        order.status = OrderStatus::Deleted;
+        HTLC_ORDERS.save(deps.storage, (&info.sender, order_id), &order)?;
 
-        order.status = OrderStatus::Confirmed;
-        SWAP_ORDERS.save(deps.storage, (&info.sender, order_id), &order)?;
-        ORDER_POINTER.remove(deps.storage);
+        Ok(Response::new()
+            .add_attribute("action", "cancel_htlc_order")
+            .add_attribute("order_id", order_id.to_string()))
+    }
 
-        // Unwrapping is save because order is atomic.
-        let taker = order.taker.unwrap();
-        let msgs = vec![
-            BankMsg::Send {
-                to_address: maker,
-                amount: vec![order.coin_out],
-            },
-            BankMsg::Send {
-                to_address: taker.into_string(),
-                amount: vec![order.coin_in],
-            },
-        ];
+    /// Allows a maker to deposit a bond. The bond is used to penalize the
+    /// maker when the settlement of an order fails and the failure policy
+    /// is `RefundAndPenalize`. The bond of an order is slashed before the
+    /// maker bond.
+    ///
+    /// # Errors
+    ///
+    /// - more than one coin is sent to the contract.
+    /// - the order is not open or expired.
+    /// - the order bond is in a different denom.
+    pub fn deposit_bond(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        order_id: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 1)?;
+        let bond = &info.funds[0];
+
+        let mut response = Response::new()
+            .add_attribute("action", "deposit_bond")
+            .add_attribute("maker", &info.sender)
+            .add_attribute("bond", bond.to_string());
+
+        let Some(order_id) = order_id else {
+            MAKER_BONDS.update(
+                deps.storage,
+                (&info.sender, &bond.denom),
+                |amount| -> StdResult<_> {
+                    Ok(amount.unwrap_or_default().checked_add(bond.amount)?)
+                },
+            )?;
+            return Ok(response);
+        };
+
+        let order = SWAP_ORDERS.load(deps.storage, (&info.sender, order_id))?;
+        let block_time = env.block.time.seconds();
+        let valid_status = match order.current_status(block_time) {
+            OrderStatus::Reserved => OrderStatus::Reserved,
+            _ => OrderStatus::Open,
+        };
+        validate_status_and_expiration(&order, valid_status, block_time)?;
+
+        let mut order_bond = ORDER_BONDS
+            .may_load(deps.storage, (&info.sender, order_id))?
+            .unwrap_or(Coin {
+                denom: bond.denom.clone(),
+                amount: Uint128::zero(),
+            });
+        ensure!(
+            order_bond.denom == bond.denom,
+            ContractError::BondDenomMismatch {
+                denom: order_bond.denom
+            }
+        );
+        order_bond.amount = order_bond.amount.checked_add(bond.amount)?;
+        ORDER_BONDS.save(deps.storage, (&info.sender, order_id), &order_bond)?;
+        response = response.add_attribute("order_id", order_id.to_string());
+
+        Ok(response)
+    }
+
+    /// Allows a maker to withdraw its bond, or the bond of one of its orders.
+    /// The maker bond can be withdrawn once all the orders of the maker are
+    /// closed, the bond of an order once the order is closed. Auctions with
+    /// bids are closed once settled, also after their timeout.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - the maker has open orders, or the order is open.
+    /// - there is no bond to withdraw.
+    pub fn withdraw_bond(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        order_id: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        let maker = info.sender;
+        let block_time = env.block.time.seconds();
+
+        let mut response = Response::new()
+            .add_attribute("action", "withdraw_bond")
+            .add_attribute("maker", &maker);
+
+        let bond = if let Some(order_id) = order_id {
+            let order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+            let open = matches!(
+                order.current_status(block_time),
+                OrderStatus::Open | OrderStatus::Reserved | OrderStatus::Accepted
+            );
+            // An ended auction with bids is closed only once settled.
+            ensure!(
+                !order.has_bids() && (!open || order.timeout < block_time),
+                ContractError::OrderNotClosed {}
+            );
+
+            let bond = ORDER_BONDS
+                .may_load(deps.storage, (&maker, order_id))?
+                .ok_or(ContractError::NoBond {})?;
+            ORDER_BONDS.remove(deps.storage, (&maker, order_id));
+            response = response.add_attribute("order_id", order_id.to_string());
+            vec![bond]
+        } else {
+            // Orders are closed once released from the maker exposure.
+            release_expired_exposure(deps.storage, &maker, block_time)?;
+            let has_open_orders = COMMITTED_ORDERS
+                .sub_prefix(&maker)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .next()
+                .is_some();
+            ensure!(!has_open_orders, ContractError::MakerHasOpenOrders {});
+
+            let bond = MAKER_BONDS
+                .prefix(&maker)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+                .collect::<StdResult<Vec<Coin>>>()?;
+            ensure!(!bond.is_empty(), ContractError::NoBond {});
+            for coin in &bond {
+                MAKER_BONDS.remove(deps.storage, (&maker, &coin.denom));
+            }
+            bond
+        };
+
+        Ok(response
+            .add_attribute(
+                "bond",
+                bond.iter()
+                    .map(Coin::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+            .add_message(BankMsg::Send {
+                to_address: maker.into_string(),
+                amount: bond,
+            }))
+    }
+
+    /// Adds the takers to the blocklist of the sender. Blocked takers cannot
+    /// accept, bid on or counter any order of the sender.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - any of the takers is not a valid address.
+    pub fn block_takers(
+        deps: DepsMut,
+        info: MessageInfo,
+        takers: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        for taker in &takers {
+            let taker = deps.api.addr_validate(taker)?;
+            BLOCKED_TAKERS.save(deps.storage, (&info.sender, &taker), &())?;
+        }
 
         Ok(Response::new()
-            .add_messages(msgs)
-            .add_attribute("action", "confirm_swap_order"))
+            .add_attribute("action", "block_takers")
+            .add_attribute("maker", info.sender)
+            .add_attribute("takers", takers.join(",")))
+    }
+
+    /// Removes the takers from the blocklist of the sender.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    pub fn unblock_takers(
+        deps: DepsMut,
+        info: MessageInfo,
+        takers: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        for taker in &takers {
+            BLOCKED_TAKERS.remove(deps.storage, (&info.sender, &Addr::unchecked(taker)));
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "unblock_takers")
+            .add_attribute("maker", info.sender)
+            .add_attribute("takers", takers.join(",")))
+    }
+
+    /// Approves `operator` to create, amend or cancel the swap orders of the
+    /// sender according to `permissions`. Orders created by the operator are
+    /// owned and settled through `x/authz` by the sender.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    /// - no permission is specified.
+    /// - `operator` is not a valid address.
+    pub fn approve_operator(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        permissions: Vec<OperatorPermission>,
+        expires: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+        ensure!(
+            !permissions.is_empty(),
+            ContractError::NoOperatorPermissions {}
+        );
+
+        let operator = deps.api.addr_validate(&operator)?;
+        let approval = OperatorApproval {
+            permissions,
+            expires: expires.map(|expires| env.block.time.plus_seconds(expires).seconds()),
+        };
+        OPERATORS.save(deps.storage, (&info.sender, &operator), &approval)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_operator")
+            .add_attribute("maker", info.sender)
+            .add_attribute("operator", operator)
+            .add_attribute(
+                "permissions",
+                approval
+                    .permissions
+                    .iter()
+                    .map(OperatorPermission::to_string)
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ))
+    }
+
+    /// Revokes the approval of `operator` given by the sender.
+    ///
+    /// # Errors
+    ///
+    /// - coins sent to the contract along with the message.
+    pub fn revoke_operator(
+        deps: DepsMut,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        validate_coins_number(&info.funds, 0)?;
+
+        OPERATORS.remove(deps.storage, (&info.sender, &Addr::unchecked(&operator)));
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_operator")
+            .add_attribute("maker", info.sender)
+            .add_attribute("operator", operator))
     }
 }
 
 pub mod query {
 
-    use cosmwasm_std::{Addr, Order};
+    use cosmwasm_std::{Addr, Coin, Order};
 
-    use crate::msg::{AllSwapOrdersResponse, SwapOrdersByMakerResponse};
-    use crate::state::{SwapOrder, SWAP_ORDERS};
+    use crate::msg::{
+        AllSwapOrdersResponse, BlockedTakersResponse, CounterOffersResponse, CurrentPriceResponse,
+        HtlcOrdersByMakerResponse, MakerBondResponse, MakerExposureResponse, MakerStatsResponse,
+        OperatorsResponse, QuoteRequestsResponse, QuotesByRequestResponse, SwapOrderResponse,
+        SwapOrdersByMakerResponse,
+    };
+    use crate::state::{
+        expired_committed_orders, CounterOffer, HtlcOrder, OperatorApproval, OrderStatus,
+        QuoteRequest, SwapOrder, BLOCKED_TAKERS, COUNTER_OFFERS, HTLC_ORDERS, MAKER_BONDS,
+        MAKER_EXPOSURE, MAKER_STATS, OPERATORS, ORDER_BONDS, QUOTES, QUOTE_REQUESTS, SWAP_ORDERS,
+    };
+    use crate::utils::current_price;
 
     use super::*;
 
@@ -309,6 +3142,15 @@ pub mod query {
         CONFIG.load(deps.storage)
     }
 
+    /// Returns the order with its status and phase at `block_time`.
+    fn order_at(mut order: SwapOrder, block_time: u64) -> SwapOrderResponse {
+        order.status = order.current_status(block_time);
+        SwapOrderResponse {
+            phase: order.current_phase(block_time),
+            order,
+        }
+    }
+
     /// Returns all active orders.
     pub fn get_all_swap_orders(deps: Deps, env: Env) -> StdResult<AllSwapOrdersResponse> {
         let current_time = env.block.time.seconds();
@@ -317,13 +3159,13 @@ pub mod query {
             .filter_map(|item| {
                 item.ok().and_then(|(addr, order)| {
                     if order.timeout > current_time {
-                        Some(Ok((addr, order)))
+                        Some(Ok((addr, order_at(order, current_time))))
                     } else {
                         None
                     }
                 })
             })
-            .collect::<StdResult<Vec<((Addr, u64), SwapOrder)>>>()?;
+            .collect::<StdResult<Vec<((Addr, u64), SwapOrderResponse)>>>()?;
         Ok(AllSwapOrdersResponse { orders })
     }
 
@@ -342,57 +3184,395 @@ pub mod query {
             .filter_map(|item| {
                 item.ok().and_then(|(addr, order)| {
                     if order.timeout > current_time {
-                        Some(Ok((addr, order)))
+                        Some(Ok((addr, order_at(order, current_time))))
                     } else {
                         None
                     }
                 })
             })
-            .collect::<StdResult<Vec<(u64, SwapOrder)>>>()?;
+            .collect::<StdResult<Vec<(u64, SwapOrderResponse)>>>()?;
 
         Ok(SwapOrdersByMakerResponse { orders })
     }
+
+    /// Returns all hash time-locked orders associated with a creator. Expired
+    /// orders are included since locked funds can still be refunded.
+    pub fn get_htlc_orders_by_maker(
+        deps: Deps,
+        maker: String,
+    ) -> StdResult<HtlcOrdersByMakerResponse> {
+        let maker = Addr::unchecked(maker);
+
+        let orders = HTLC_ORDERS
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u64, HtlcOrder)>>>()?;
+
+        Ok(HtlcOrdersByMakerResponse { orders })
+    }
+
+    /// Returns the coins committed by a maker in open orders. Expired orders not
+    /// yet released from the stored exposure are excluded.
+    pub fn get_maker_exposure(
+        deps: Deps,
+        env: Env,
+        maker: String,
+    ) -> StdResult<MakerExposureResponse> {
+        let maker = Addr::unchecked(maker);
+
+        let mut exposure = MAKER_EXPOSURE
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<Vec<Coin>>>()?;
+
+        for (_, order_id) in
+            expired_committed_orders(deps.storage, &maker, env.block.time.seconds())?
+        {
+            let order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+            if let Some(coin) = exposure
+                .iter_mut()
+                .find(|coin| coin.denom == order.coin_in.denom)
+            {
+                coin.amount = coin.amount.checked_sub(order.coin_in.amount)?;
+            }
+        }
+        exposure.retain(|coin| !coin.amount.is_zero());
+
+        Ok(MakerExposureResponse { exposure })
+    }
+
+    /// Returns the order counters of a maker and whether the maker is
+    /// suspended by the suspension rule.
+    pub fn get_maker_stats(deps: Deps, env: Env, maker: String) -> StdResult<MakerStatsResponse> {
+        let stats = MAKER_STATS
+            .may_load(deps.storage, &Addr::unchecked(maker))?
+            .unwrap_or_default();
+        let suspended = CONFIG
+            .load(deps.storage)?
+            .suspension_rule
+            .is_some_and(|rule| stats.is_suspended(&rule, env.block.time.seconds()));
+
+        Ok(MakerStatsResponse { stats, suspended })
+    }
+
+    /// Returns the bond of a maker and the bonds of its orders.
+    pub fn get_maker_bond(deps: Deps, maker: String) -> StdResult<MakerBondResponse> {
+        let maker = Addr::unchecked(maker);
+
+        let bond = MAKER_BONDS
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<Vec<Coin>>>()?;
+        let order_bonds = ORDER_BONDS
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u64, Coin)>>>()?;
+
+        Ok(MakerBondResponse { bond, order_bonds })
+    }
+
+    /// Returns the takers blocked by a maker.
+    pub fn get_blocked_takers(deps: Deps, maker: String) -> StdResult<BlockedTakersResponse> {
+        let maker = Addr::unchecked(maker);
+        let takers = BLOCKED_TAKERS
+            .prefix(&maker)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+
+        Ok(BlockedTakersResponse { takers })
+    }
+
+    /// Returns the operators approved by a maker.
+    pub fn get_operators(deps: Deps, maker: String) -> StdResult<OperatorsResponse> {
+        let maker = Addr::unchecked(maker);
+        let operators = OPERATORS
+            .prefix(&maker)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, OperatorApproval)>>>()?;
+
+        Ok(OperatorsResponse { operators })
+    }
+
+    /// Returns the counter-offers of a swap order.
+    pub fn get_counter_offers(
+        deps: Deps,
+        maker: String,
+        order_id: u64,
+    ) -> StdResult<CounterOffersResponse> {
+        let maker = Addr::unchecked(maker);
+        let offers = COUNTER_OFFERS
+            .prefix((&maker, order_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, CounterOffer)>>>()?;
+
+        Ok(CounterOffersResponse { offers })
+    }
+
+    /// Returns the quote requests open and not expired.
+    pub fn get_open_quote_requests(deps: Deps, env: Env) -> StdResult<QuoteRequestsResponse> {
+        let current_time = env.block.time.seconds();
+        let requests = QUOTE_REQUESTS
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| match item {
+                Ok((_, request)) => {
+                    request.status == OrderStatus::Open && request.expiry >= current_time
+                }
+                Err(_) => true,
+            })
+            .collect::<StdResult<Vec<(u64, QuoteRequest)>>>()?;
+
+        Ok(QuoteRequestsResponse { requests })
+    }
+
+    /// Returns all the quotes submitted for a quote request.
+    pub fn get_quotes_by_request(
+        deps: Deps,
+        env: Env,
+        request_id: u64,
+    ) -> StdResult<QuotesByRequestResponse> {
+        let current_time = env.block.time.seconds();
+        let quotes = QUOTES
+            .sub_prefix(request_id)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (maker, order_id) = item?;
+                let order = SWAP_ORDERS.load(deps.storage, (&maker, order_id))?;
+                Ok(((maker, order_id), order_at(order, current_time)))
+            })
+            .collect::<StdResult<Vec<((Addr, u64), SwapOrderResponse)>>>()?;
+
+        Ok(QuotesByRequestResponse { quotes })
+    }
+
+    /// Returns the amount of `coin_out` required to accept a swap order at the
+    /// current block time.
+    pub fn get_current_price(
+        deps: Deps,
+        env: Env,
+        maker: String,
+        order_id: u64,
+    ) -> StdResult<CurrentPriceResponse> {
+        let order = SWAP_ORDERS.load(deps.storage, (&Addr::unchecked(maker), order_id))?;
+
+        Ok(CurrentPriceResponse {
+            price: current_price(&order, env.block.time.seconds()),
+        })
+    }
 }
 
 pub mod reply {
-    use cosmwasm_std::{BankMsg, Coin, DepsMut, Response};
+    use cosmwasm_std::{BankMsg, DepsMut, Env, Event, Response, Storage};
 
     use crate::error::ContractError;
-    use crate::state::{OrderPointer, OrderStatus, ORDER_POINTER, SWAP_ORDERS};
+    use crate::state::{
+        add_to_order_book, commit_exposure, refund_counter_offers, release_exposure, slash_bonds,
+        update_maker_stats, FailurePolicy, OrderKind, OrderPointer, OrderStatus, ACCEPTED_OFFERS,
+        MATCHED_ORDERS, PENDING_MATCHES, QUOTE_REQUESTS, SWAP_ORDERS,
+    };
+    use crate::utils::bounded_failure_reason;
+
+    use super::CONFIG;
 
     /// Handler the error during the execution of `ConfirmSwapOrder` sent via `x/authz`
-    /// module.
-    /// NOTE: currently all order status are not used but still included to
-    /// easily extend functionalities in the future.
-    pub fn reply_confirm_order(deps: DepsMut) -> Result<Response, ContractError> {
+    /// module. The error is stored in the orders to explain why they failed and the
+    /// taker is refunded. The orders are then handled according to the failure policy
+    /// of the contract.
+    pub fn reply_confirm_orders(
+        deps: DepsMut,
+        env: Env,
+        order_pointers: Vec<OrderPointer>,
+        error: String,
+    ) -> Result<Response, ContractError> {
+        let failure_reason = bounded_failure_reason(&error);
+        let config = CONFIG.load(deps.storage)?;
+        let window = config.suspension_rule.map(|rule| rule.window);
+
+        let mut response = Response::new()
+            .add_attribute("action", "reply")
+            .add_attribute("reason", "order_execution_failed")
+            .add_attribute("failure_reason", &failure_reason);
+        for order_pointer in order_pointers {
+            update_maker_stats(deps.storage, &order_pointer.maker, |stats| {
+                stats.record_settlement(false, env.block.time.seconds(), window)
+            })?;
+            let (msgs, event) = handle_failed_order(
+                deps.storage,
+                order_pointer,
+                &failure_reason,
+                &config.failure_policy,
+            )?;
+            response = response.add_messages(msgs).add_event(event);
+        }
+
+        Ok(response)
+    }
+
+    /// Handles the error during the settlement of two crossed orders. The orders
+    /// are opened again, the error is stored to explain why the settlement failed,
+    /// and the failure is recorded in the stats of the maker whose settlement
+    /// failed. If the resting order has already been confirmed, its `coin_in` is
+    /// refunded to its maker.
+    ///
+    /// The incoming order settlement is executed, and replies, also after the
+    /// resting one failed, so in that case the settlement context is kept until
+    /// its reply, which finds both orders already open.
+    pub fn reply_match_orders(
+        deps: DepsMut,
+        env: Env,
+        reply_id: u64,
+        order_pointers: Vec<OrderPointer>,
+        error: String,
+    ) -> Result<Response, ContractError> {
+        let failure_reason = bounded_failure_reason(&error);
+        let window = CONFIG
+            .load(deps.storage)?
+            .suspension_rule
+            .map(|rule| rule.window);
+
+        let mut response = Response::new()
+            .add_attribute("action", "reply")
+            .add_attribute("reason", "match_execution_failed")
+            .add_attribute("failure_reason", &failure_reason);
+        let orders = order_pointers
+            .into_iter()
+            .map(
+                |OrderPointer {
+                     order_id, maker, ..
+                 }| {
+                    let order = SWAP_ORDERS
+                        .may_load(deps.storage, (&maker, order_id))?
+                        .ok_or(ContractError::Unauthorized)?;
+                    Ok((maker, order_id, order))
+                },
+            )
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        // The reply of the incoming order settlement is still to come if the
+        // resting one failed.
+        if orders[0].2.status != OrderStatus::Accepted {
+            PENDING_MATCHES.remove(deps.storage, reply_id);
+        }
+
+        // The first order still accepted is the one whose settlement failed.
+        let mut failure_recorded = false;
+        for (maker, order_id, mut order) in orders {
+            match order.status {
+                OrderStatus::Accepted if !failure_recorded => {
+                    update_maker_stats(deps.storage, &maker, |stats| {
+                        stats.record_settlement(false, env.block.time.seconds(), window)
+                    })?;
+                    failure_recorded = true;
+                }
+                OrderStatus::Accepted => {}
+                OrderStatus::Confirmed => {
+                    response = response.add_message(BankMsg::Send {
+                        to_address: maker.to_string(),
+                        amount: vec![order.coin_in.clone()],
+                    });
+                    commit_exposure(deps.storage, &maker, order_id, &order)?;
+                    add_to_order_book(deps.storage, &maker, order_id, &order)?;
+                }
+                // Already opened again by the reply of the resting order settlement.
+                _ => continue,
+            }
+            order.status = OrderStatus::Open;
+            order.taker = None;
+            order.failure_reason = Some(failure_reason.clone());
+            SWAP_ORDERS.save(deps.storage, (&maker, order_id), &order)?;
+            MATCHED_ORDERS.remove(deps.storage, (&maker, order_id));
+
+            response = response.add_event(
+                Event::new("swap_order_reopened")
+                    .add_attribute("order_id", order_id.to_string())
+                    .add_attribute("maker", maker),
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Applies the failure policy to an order whose settlement failed. Returns
+    /// the messages to refund the taker and the event describing the outcome.
+    /// Auctions are closed also when the policy reopens the orders.
+    fn handle_failed_order(
+        storage: &mut dyn Storage,
+        order_pointer: OrderPointer,
+        failure_reason: &str,
+        failure_policy: &FailurePolicy,
+    ) -> Result<(Vec<BankMsg>, Event), ContractError> {
         let OrderPointer {
             order_id,
             maker,
             taker,
-        } = ORDER_POINTER.load(deps.storage)?;
+        } = order_pointer;
 
-        let mut coin_out = Coin::default();
-        SWAP_ORDERS.update(
-            deps.storage,
-            (&maker, order_id),
-            |swap_order| match swap_order {
-                Some(mut order) => {
-                    order.status = OrderStatus::Failed;
-                    coin_out = order.coin_out.clone();
-                    Ok(order)
-                }
-                None => Err(ContractError::Unauthorized),
-            },
-        )?;
-        ORDER_POINTER.remove(deps.storage);
-        let refund_msg = BankMsg::Send {
+        let mut order = SWAP_ORDERS
+            .may_load(storage, (&maker, order_id))?
+            .ok_or(ContractError::Unauthorized)?;
+        order.failure_reason = Some(failure_reason.to_string());
+
+        // The taker of a counter-offer is refunded the offered coin.
+        let paid = ACCEPTED_OFFERS
+            .may_load(storage, (&maker, order_id))?
+            .unwrap_or_else(|| order.coin_out.clone());
+        ACCEPTED_OFFERS.remove(storage, (&maker, order_id));
+        let mut msgs = vec![BankMsg::Send {
             to_address: taker.to_string(),
-            amount: vec![coin_out],
+            amount: vec![paid],
+        }];
+
+        // The request of a failed quote is open again to receive new quotes.
+        if let Some(request_id) = order.quote_request {
+            let mut request = QUOTE_REQUESTS.load(storage, request_id)?;
+            request.status = OrderStatus::Open;
+            QUOTE_REQUESTS.save(storage, request_id, &request)?;
+        }
+
+        let is_auction = matches!(order.kind, OrderKind::English { .. });
+        let event = match failure_policy {
+            // The order keeps counting in the maker exposure since it can
+            // still be accepted.
+            FailurePolicy::RefundAndReopen if !is_auction => {
+                order.status = OrderStatus::Open;
+                order.taker = None;
+                add_to_order_book(storage, &maker, order_id, &order)?;
+                Event::new("swap_order_reopened")
+            }
+            // An auction is settled after its end without bids left, so it
+            // cannot be reopened.
+            FailurePolicy::RefundAndClose | FailurePolicy::RefundAndReopen => {
+                order.status = OrderStatus::Failed;
+                release_exposure(storage, &maker, order_id, &order)?;
+                Event::new("swap_order_closed")
+            }
+            FailurePolicy::RefundAndPenalize { penalty } => {
+                order.status = OrderStatus::Failed;
+                release_exposure(storage, &maker, order_id, &order)?;
+
+                let penalty = slash_bonds(storage, &maker, order_id, penalty)?;
+                if !penalty.amount.is_zero() {
+                    msgs.push(BankMsg::Send {
+                        to_address: taker.to_string(),
+                        amount: vec![penalty.clone()],
+                    });
+                }
+                Event::new("maker_penalized").add_attribute("penalty", penalty.to_string())
+            }
         };
+        SWAP_ORDERS.save(storage, (&maker, order_id), &order)?;
+        // The offers attached to a failed order cannot be accepted anymore.
+        if order.status == OrderStatus::Failed {
+            msgs.extend(refund_counter_offers(storage, &maker, order_id, |_| true)?);
+        }
 
-        Ok(Response::new()
-            .add_message(refund_msg)
-            .add_attribute("action", "reply")
-            .add_attribute("reason", "order_execution_failed"))
+        Ok((
+            msgs,
+            event
+                .add_attribute("order_id", order_id.to_string())
+                .add_attribute("maker", maker)
+                .add_attribute("taker", taker),
+        ))
     }
 }
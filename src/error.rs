@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -34,8 +34,194 @@ pub enum ContractError {
     #[error("maker cannot accept its own order")]
     SenderIsMaker {},
 
+    #[error("taker {taker} is blocked by the maker {maker}")]
+    TakerBlocked { maker: String, taker: String },
+
+    #[error("{operator} is not approved by {maker} to {permission}")]
+    OperatorNotApproved {
+        operator: String,
+        maker: String,
+        permission: String,
+    },
+
+    #[error("operator approval expired at block time {expiration}")]
+    OperatorApprovalExpired { expiration: u64 },
+
+    #[error("at least one operator permission must be specified")]
+    NoOperatorPermissions {},
+
+    #[error("order is reserved by {taker} until block time {expiration}")]
+    OrderReserved { taker: String, expiration: u64 },
+
+    #[error("reservations are not enabled in the market")]
+    ReservationsNotEnabled {},
+
+    #[error("reservation duration must be greater than zero and at most {max} seconds")]
+    InvalidReservationDuration { max: u64 },
+
+    #[error("reservation deposit cannot be zero")]
+    ZeroReservationDeposit {},
+
+    #[error("receipts are not enabled in the market")]
+    ReceiptsNotEnabled {},
+
+    #[error("the order receipt has been transferred to {holder}")]
+    ReceiptTransferred { holder: String },
+
+    #[error("only fixed price swap orders can be amended")]
+    OrderNotAmendable {},
+
+    #[error("reserved_for must be greater than zero and less than the order timeout of {timeout} seconds")]
+    InvalidReservedFor { timeout: u64 },
+
+    #[error("orders reserved for a period require designated takers")]
+    NoDesignatedTakers {},
+
+    #[error("order bond must be deposited in {denom}")]
+    BondDenomMismatch { denom: String },
+
+    #[error("bond cannot be withdrawn while the maker has open orders")]
+    MakerHasOpenOrders {},
+
+    #[error("order bond cannot be withdrawn while the order is open")]
+    OrderNotClosed {},
+
+    #[error("no bond to withdraw")]
+    NoBond {},
+
+    #[error("suspension rule requires a failure rate of at most 1 and a non-zero window")]
+    InvalidSuspensionRule {},
+
+    #[error("maker is suspended for failing too many settlements")]
+    MakerSuspended {},
+
     #[error("unable to encode json")]
     JsonEncodeError(),
+
+    #[error("hash lock must be a sha256 hash of 32 bytes, received {length} bytes")]
+    InvalidHashLock { length: u64 },
+
+    #[error("merkle root must be a sha256 hash of 32 bytes, received {length} bytes")]
+    InvalidMerkleRoot { length: u64 },
+
+    #[error("merkle proof does not match the order root")]
+    InvalidMerkleProof {},
+
+    #[error("taker {taker} is not a member of the group {group}")]
+    NotGroupMember { taker: String, group: String },
+
+    #[error("taker weight {weight} in the group is lower than the minimum {min_weight}")]
+    GroupWeightTooLow { weight: u64, min_weight: u64 },
+
+    #[error("address {address} is not allowed to {action}")]
+    AddressNotAllowed { address: String, action: String },
+
+    #[error("compliance hook query failed: {reason}")]
+    ComplianceHookFailed { reason: String },
+
+    #[error("preimage does not match the hash lock")]
+    InvalidPreimage {},
+
+    #[error("time lock cannot be zero")]
+    ZeroTimeLock {},
+
+    #[error("time lock expired at block time {expiration}")]
+    TimeLockExpired { expiration: u64 },
+
+    #[error("time lock not expired: expiration block time {expiration}")]
+    TimeLockNotExpired { expiration: u64 },
+
+    #[error("maker did not grant a ContractExecutionAuthorization to this contract")]
+    MissingAuthzGrant {},
+
+    #[error("authz grant expires at block time {expiration} before the order timeout {timeout}")]
+    GrantExpiresBeforeTimeout { expiration: u64, timeout: u64 },
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("maker has insufficient funds: balance {balance}{denom}, required {required}{denom}")]
+    MakerInsufficientFunds {
+        denom: String,
+        balance: u128,
+        required: u128,
+    },
+
+    #[error("at least one order must be specified")]
+    EmptyBatch {},
+
+    #[error("sent funds not enough: available {available}{denom}, required {required}{denom}")]
+    InsufficientBatchFunds {
+        denom: String,
+        available: u128,
+        required: u128,
+    },
+
+    #[error("orders don't cross: {order_id} and {counter_order_id}")]
+    OrdersNotCrossing {
+        order_id: u64,
+        counter_order_id: u64,
+    },
+
+    #[error("end amount {end_amount} must be greater than zero and lower than the start amount {start_amount}")]
+    InvalidEndAmount {
+        start_amount: u128,
+        end_amount: u128,
+    },
+
+    #[error("price step cannot be zero")]
+    ZeroStep {},
+
+    #[error("auction orders can only be bid on")]
+    AuctionOrder {},
+
+    #[error("order is not an auction")]
+    NotAnAuction {},
+
+    #[error("auction not ended: end block time {end}")]
+    AuctionNotEnded { end: u64 },
+
+    #[error("auction has no bids")]
+    AuctionHasNoBids {},
+
+    #[error("auction cannot be cancelled after a bid")]
+    AuctionHasBids {},
+
+    #[error("minimum bid increment cannot be zero")]
+    ZeroBidIncrement {},
+
+    #[error("quote request not available: status {status}, expiration block time {expiration}")]
+    QuoteRequestNotAvailable { status: String, expiration: u64 },
+
+    #[error("quote request reached the maximum number of quotes: {max}")]
+    TooManyQuotes { max: u32 },
+
+    #[error("order {order_id} is not a quote of the request {request_id}")]
+    QuoteNotFound { request_id: u64, order_id: u64 },
+
+    #[error(
+        "order is a quote of the request {request_id} and can be accepted only with accept_quote"
+    )]
+    QuoteOrder { request_id: u64 },
+
+    #[error("too many allowed takers: the maximum is {max}")]
+    TooManyAllowedTakers { max: usize },
+
+    #[error("order reached the maximum number of counter-offers: {max}")]
+    TooManyCounterOffers { max: u32 },
+
+    #[error("counter-offer expired at block time {expiration}")]
+    CounterOfferExpired { expiration: u64 },
+
+    #[error("failure policy penalty cannot be zero")]
+    ZeroPenalty {},
+
+    #[error("authz grant allows to send {limit}{denom}, required {required}{denom}")]
+    GrantLimitExceeded {
+        denom: String,
+        limit: u128,
+        required: u128,
+    },
 }
 
 #[derive(Error, Debug)]
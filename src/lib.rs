@@ -1,5 +1,6 @@
 pub mod contract;
 mod error;
+pub mod merkle;
 pub mod msg;
 pub mod state;
 pub mod utils;
@@ -0,0 +1,90 @@
+use cosmwasm_std::HexBinary;
+use sha2::{Digest, Sha256};
+
+/// Sha256 hash of a node of the tree.
+pub type Hash = [u8; 32];
+
+/// Returns the leaf of the tree associated with an address.
+pub fn leaf_hash(address: &str) -> Hash {
+    Sha256::digest(address.as_bytes()).into()
+}
+
+/// Returns the parent of two nodes. Nodes are sorted before hashing so that a
+/// proof doesn't need to specify the position of each sibling.
+pub fn hash_pair(first: &Hash, second: &Hash) -> Hash {
+    let (left, right) = if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Check that `address` is a leaf of the tree with the given `root` by hashing
+/// the leaf with the siblings in `proof`.
+pub fn verify_proof(root: &[u8], address: &str, proof: &[HexBinary]) -> bool {
+    let mut computed = leaf_hash(address);
+    for sibling in proof {
+        let Ok(sibling) = Hash::try_from(sibling.as_slice()) else {
+            return false;
+        };
+        computed = hash_pair(&computed, &sibling);
+    }
+    computed[..] == root[..]
+}
+
+/// Merkle tree of addresses used offline to compute the root stored in a swap
+/// order and the proofs sent by the takers.
+pub struct MerkleTree {
+    /// Levels of the tree, from the leaves to the root.
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Build the tree of the given addresses. A node without sibling is moved
+    /// to the next level as it is.
+    pub fn new<T: AsRef<str>>(addresses: &[T]) -> Self {
+        let mut layers = vec![addresses
+            .iter()
+            .map(|address| leaf_hash(address.as_ref()))
+            .collect::<Vec<Hash>>()];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    /// Returns the root of the tree, or None if the tree has no leaves.
+    pub fn root(&self) -> Option<HexBinary> {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .map(|root| HexBinary::from(root.as_slice()))
+    }
+
+    /// Returns the proof of `address`, or None if it is not a leaf of the tree.
+    pub fn proof(&self, address: &str) -> Option<Vec<HexBinary>> {
+        let leaf = leaf_hash(address);
+        let mut index = self.layers[0].iter().position(|node| *node == leaf)?;
+
+        let mut proof = vec![];
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(HexBinary::from(sibling.as_slice()));
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
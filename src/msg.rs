@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, HexBinary, Uint128};
 
-use crate::state::{Config, SwapOrder};
+use crate::state::{
+    Config, CounterOffer, FailurePolicy, HtlcOrder, MakerStats, OperatorApproval,
+    OperatorPermission, OrderPhase, QuoteRequest, ReservationPolicy, SuspensionRule, SwapOrder,
+};
 
 /// This structure contains required variables to instantiate a new market.
 #[cw_serde]
@@ -17,22 +20,230 @@ pub enum ExecuteMsg {
     /// Only owner can update.
     UpdateConfig {
         /// New contract owner.
-        new_owner: String,
+        new_owner: Option<String>,
+        /// If true, the creation of an order requires the maker balance to
+        /// cover all the open orders selling the same denom.
+        check_maker_balance: Option<bool>,
+        /// Action taken when the settlement of an accepted order fails.
+        failure_policy: Option<FailurePolicy>,
+        /// Sets or removes the cw4 group whose members can accept the orders
+        /// of the market.
+        taker_group: Option<TakerGroupUpdate>,
+        /// Sets or removes the contract queried to screen makers and takers.
+        compliance_hook: Option<ComplianceHookUpdate>,
+        /// Sets or removes the cw721 contract minting the order receipts.
+        receipt_contract: Option<ReceiptContractUpdate>,
+        /// Enables or disables the reservations of the orders.
+        reservation_policy: Option<ReservationPolicyUpdate>,
+        /// Sets or removes the rule suspending the makers failing too many
+        /// settlements.
+        suspension_rule: Option<SuspensionRuleUpdate>,
     },
     /// Allows a user to create a swap order. The execution of the order
     /// requires the user to have granted a `ContractExecutionAuthorization`
     /// to this smart contract via the `x/authz` Cosmos SDK module with the
     /// allowance to spend `coin_in`.
     CreateSwapOrder {
+        /// Maker of the order if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
         /// Coin to send.
         coin_in: Coin,
         /// Coin to received.
         coin_out: Coin,
-        /// If specified, is the only counterparty accepted in the swap.
-        taker: Option<String>,
+        /// If not empty, are the only counterparties accepted in the swap.
+        #[serde(default)]
+        allowed_takers: Vec<String>,
+        /// If specified, root of the merkle tree of the addresses that can
+        /// accept the deal, built with `merkle::MerkleTree`.
+        #[serde(default)]
+        taker_merkle_root: Option<HexBinary>,
+        /// If specified, only the members of the cw4 group can accept the deal.
+        #[serde(default)]
+        taker_group: Option<TakerGroupParams>,
+        /// If true, a cw721 receipt of the order is minted to the maker. The
+        /// proceeds of the order are sent to the holder of the receipt.
+        #[serde(default)]
+        mint_receipt: bool,
+        /// If specified, duration in seconds during which only the takers
+        /// designated by `allowed_takers`, `taker_merkle_root` and
+        /// `taker_group` can accept the deal. Afterwards anyone can accept it.
+        #[serde(default)]
+        reserved_for: Option<u64>,
         /// Timestamp after which the deal expires in seconds.
         timeout: u64,
     },
+    /// Allows a user to create a swap order whose price decreases over time.
+    /// The taker pays the current price and the overpayment is refunded.
+    CreateDutchSwapOrder {
+        /// Maker of the order if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// Coin to send.
+        coin_in: Coin,
+        /// Coin to received at the creation of the order.
+        coin_out: Coin,
+        /// Amount of `coin_out` to receive at the order timeout.
+        end_amount: Uint128,
+        /// If specified, the price decreases only every `step` seconds.
+        step: Option<u64>,
+        /// If not empty, are the only counterparties accepted in the swap.
+        #[serde(default)]
+        allowed_takers: Vec<String>,
+        /// Timestamp after which the deal expires in seconds.
+        timeout: u64,
+    },
+    /// Allows a user to auction `coin_in` to the highest bidder until the timeout.
+    CreateAuctionSwapOrder {
+        /// Maker of the order if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// Coin to send.
+        coin_in: Coin,
+        /// Denom of the bids and reserve price of the auction.
+        coin_out: Coin,
+        /// Minimum amount by which a bid has to exceed the highest one.
+        min_increment: Uint128,
+        /// If specified, a bid placed less than `extension` seconds before the
+        /// timeout extends the auction to `extension` seconds after the bid.
+        extension: Option<u64>,
+        /// Timestamp after which the auction ends in seconds.
+        timeout: u64,
+    },
+    /// Allows a user to bid on an auction by sending the bid amount. The
+    /// previous highest bid is refunded.
+    BidSwapOrder { order_id: u64, maker: String },
+    /// Allows anyone to settle an ended auction with the highest bidder.
+    SettleAuction { order_id: u64, maker: String },
+    /// Allows a user to ask market makers for quotes to receive `want` in
+    /// exchange of `offer_denom`.
+    CreateQuoteRequest {
+        /// Coin to receive.
+        want: Coin,
+        /// Denom to send.
+        offer_denom: String,
+        /// Duration in seconds after which quotes cannot be submitted or accepted.
+        expiry: u64,
+    },
+    /// Allows the requester to cancel an open quote request.
+    CancelQuoteRequest { request_id: u64 },
+    /// Allows a market maker to quote a request. The quote is a swap order
+    /// reserved to the requester and validated as in `CreateSwapOrder`.
+    SubmitQuote {
+        request_id: u64,
+        /// Maker of the quote if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// Amount of `offer_denom` asked to the requester.
+        price: Uint128,
+        /// Duration in seconds after which the quote expires.
+        timeout: u64,
+    },
+    /// Allows the requester to accept a quote by sending its price. The other
+    /// quotes of the request are cancelled.
+    AcceptQuote {
+        request_id: u64,
+        maker: String,
+        order_id: u64,
+    },
+    /// Allows a user to create several swap orders. Each order is validated
+    /// as in `CreateSwapOrder`.
+    CreateSwapOrders {
+        /// Maker of the orders if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        orders: Vec<SwapOrderParams>,
+    },
+    /// Allows a maker to change the price or the timeout of an open fixed
+    /// price swap order.
+    AmendSwapOrder {
+        order_id: u64,
+        /// Maker of the order if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// New amount of `coin_out` to receive.
+        price: Option<Uint128>,
+        /// New duration in seconds after which the deal expires.
+        timeout: Option<u64>,
+    },
+    /// Allows a maker to cancel its open swap orders.
+    CancelSwapOrders {
+        /// Maker of the orders if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// Identifiers of the orders to cancel.
+        order_ids: Vec<u64>,
+    },
+    /// Allows a maker to cancel all its open swap orders.
+    CancelAllMyOrders {
+        /// Maker of the orders if the sender is one of its operators. Defaults
+        /// to the sender.
+        #[serde(default)]
+        maker: Option<String>,
+        /// If specified, only the orders swapping the two denoms, in any
+        /// direction, are cancelled.
+        denom_pair: Option<(String, String)>,
+    },
+    /// Allows anyone to settle two crossing orders of different makers. The
+    /// order created first is the resting one and receives the price improvement.
+    MatchOrders {
+        /// Maker and identifier of an order.
+        order: (String, u64),
+        /// Maker and identifier of the opposite order.
+        counter_order: (String, u64),
+        /// Merkle proof of the maker of `order` for the opposite order, as in
+        /// `AcceptSwapOrder`.
+        #[serde(default)]
+        proof: Vec<HexBinary>,
+        /// Merkle proof of the maker of `counter_order` for `order`.
+        #[serde(default)]
+        counter_proof: Vec<HexBinary>,
+    },
+    /// Allows a user to offer a different `coin_out` for an open swap order. The
+    /// offered coin is sent along with the message and escrowed in the contract.
+    /// A previous offer of the sender on the same order is refunded.
+    MakeCounterOffer {
+        order_id: u64,
+        maker: String,
+        /// Duration in seconds after which the offer cannot be accepted.
+        expiry: u64,
+        /// Merkle proof of the sender, as in `AcceptSwapOrder`.
+        #[serde(default)]
+        proof: Vec<HexBinary>,
+    },
+    /// Allows a user to withdraw its counter-offer.
+    WithdrawCounterOffer { order_id: u64, maker: String },
+    /// Allows a maker to accept a counter-offer. The order is settled for the
+    /// offered coin and the other counter-offers are refunded.
+    AcceptCounterOffer {
+        order_id: u64,
+        taker: String,
+        /// Merkle proof of the taker, as in `AcceptSwapOrder`.
+        #[serde(default)]
+        proof: Vec<HexBinary>,
+    },
+    /// Allows anyone to refund the expired counter-offers of an order, or all
+    /// of them if the order is no longer open.
+    RefundCounterOffers { order_id: u64, maker: String },
+    /// Allows a user to reserve a swap order for `duration` seconds by sending
+    /// the deposit of the reservation policy. During the reservation only the
+    /// sender can accept the order.
+    ReserveSwapOrder {
+        order_id: u64,
+        maker: String,
+        /// Duration in seconds of the reservation.
+        duration: u64,
+        /// Merkle proof of the sender, as in `AcceptSwapOrder`.
+        #[serde(default)]
+        proof: Vec<HexBinary>,
+    },
     /// Allows a user to accept an existing swap order. The function requires
     /// to send along with the transaction required funds.
     AcceptSwapOrder {
@@ -41,6 +252,23 @@ pub enum ExecuteMsg {
         /// The maker associated with the order.
         // TODO: add a way to retrieve an order from the id for a better UX.
         maker: String,
+        /// Merkle proof of the sender, required if the order has a merkle root
+        /// and the sender is not in its `allowed_takers`.
+        #[serde(default)]
+        proof: Vec<HexBinary>,
+    },
+    /// Allows a user to accept several swap orders, specified as maker and
+    /// order identifier, sending along with the transaction the sum of the
+    /// required funds. Funds not used are refunded.
+    AcceptSwapOrders {
+        /// Makers and identifiers of the swap orders to match.
+        orders: Vec<(String, u64)>,
+        /// Describes how to handle orders that cannot be accepted or settled.
+        mode: AcceptMode,
+        /// Merkle proofs of the sender, as in `AcceptSwapOrder`, for the orders
+        /// at the same position. Orders without a proof get an empty one.
+        #[serde(default)]
+        proofs: Vec<Vec<HexBinary>>,
     },
     /// This message is sent by the `x/authz` module to complete an swap order
     /// after another user tried to match it with the `AcceptSwapOrder`
@@ -52,6 +280,83 @@ pub enum ExecuteMsg {
         // TODO: add a way to retrieve an order from the id for a better UX.
         maker: String,
     },
+    /// Allows a user to create a hash time-locked order. Differently from
+    /// `CreateSwapOrder`, no `x/authz` grant is required since the maker
+    /// sends `coin_in` along with the preimage when claiming the order.
+    CreateHtlcOrder {
+        /// Coin to send.
+        coin_in: Coin,
+        /// Coin to received.
+        coin_out: Coin,
+        /// If specified, is the only counterparty accepted in the swap.
+        taker: Option<String>,
+        /// Sha256 hash of the secret known by the maker.
+        hash_lock: HexBinary,
+        /// Duration in seconds during which the taker funds stay locked.
+        time_lock: u64,
+        /// Timestamp after which the deal expires in seconds.
+        timeout: u64,
+    },
+    /// Allows a user to lock the funds required by an existing hash
+    /// time-locked order.
+    LockHtlcOrder {
+        /// Identifier of the order the user wants to lock.
+        order_id: u64,
+        /// The maker associated with the order.
+        maker: String,
+    },
+    /// Allows the maker of a locked order to claim the taker funds by
+    /// revealing the preimage of the hash lock. The maker has to send
+    /// `coin_in` along with the message, which is released to the taker.
+    ClaimHtlcOrder {
+        /// Identifier of the order to claim.
+        order_id: u64,
+        /// Secret whose sha256 hash is equal to the order hash lock.
+        preimage: HexBinary,
+    },
+    /// Allows the maker or the taker of a locked order to return the locked
+    /// funds to the taker once the time lock is expired.
+    RefundHtlcOrder {
+        /// Identifier of the order to refund.
+        order_id: u64,
+        /// The maker associated with the order.
+        maker: String,
+    },
+    /// Allows the maker to cancel a hash time-locked order that has not been
+    /// locked by a taker yet.
+    CancelHtlcOrder {
+        /// Identifier of the order to cancel.
+        order_id: u64,
+    },
+    /// Allows a maker to deposit a bond used to penalize the maker when the
+    /// settlement of an order fails under the `RefundAndPenalize` policy.
+    DepositBond {
+        /// If specified, open order of the sender whose failure is penalized
+        /// with the bond before the maker bond.
+        #[serde(default)]
+        order_id: Option<u64>,
+    },
+    /// Allows a maker to withdraw its bond once all its orders are closed, or
+    /// the bond of an order once the order is closed.
+    WithdrawBond {
+        #[serde(default)]
+        order_id: Option<u64>,
+    },
+    /// Allows a maker to prevent the takers from accepting any of its orders.
+    BlockTakers { takers: Vec<String> },
+    /// Allows a maker to remove takers from its blocklist.
+    UnblockTakers { takers: Vec<String> },
+    /// Allows a maker to approve an operator to manage its swap orders. A
+    /// previous approval of the same operator is replaced.
+    ApproveOperator {
+        operator: String,
+        /// Actions the operator can perform on behalf of the maker.
+        permissions: Vec<OperatorPermission>,
+        /// If specified, duration in seconds after which the approval expires.
+        expires: Option<u64>,
+    },
+    /// Allows a maker to revoke the approval of an operator.
+    RevokeOperator { operator: String },
 }
 
 /// This enum describes available contract's query messages.
@@ -69,16 +374,262 @@ pub enum QueryMsg {
     #[returns(SwapOrdersByMakerResponse)]
     /// Retrieve all active swap orders by maker.
     SwapOrdersByMaker { maker: String },
+    #[returns(HtlcOrdersByMakerResponse)]
+    /// Retrieve all hash time-locked orders by maker, including the
+    /// expired ones that can still be refunded.
+    HtlcOrdersByMaker { maker: String },
+    #[returns(MakerExposureResponse)]
+    /// Retrieve the coins committed by a maker in open orders.
+    MakerExposure { maker: String },
+    #[returns(MakerStatsResponse)]
+    /// Retrieve the order counters of a maker and whether it is suspended.
+    MakerStats { maker: String },
+    #[returns(MakerBondResponse)]
+    /// Retrieve the bond of a maker and the bonds of its orders.
+    MakerBond { maker: String },
+    #[returns(BlockedTakersResponse)]
+    /// Retrieve the takers blocked by a maker.
+    BlockedTakers { maker: String },
+    #[returns(OperatorsResponse)]
+    /// Retrieve the operators approved by a maker, including the expired ones.
+    Operators { maker: String },
+    #[returns(QuoteRequestsResponse)]
+    /// Retrieve all open quote requests.
+    OpenQuoteRequests {},
+    #[returns(QuotesByRequestResponse)]
+    /// Retrieve all quotes submitted for a quote request.
+    QuotesByRequest { request_id: u64 },
+    #[returns(CounterOffersResponse)]
+    /// Retrieve the counter-offers of a swap order.
+    CounterOffers { maker: String, order_id: u64 },
+    #[returns(CurrentPriceResponse)]
+    /// Retrieve the amount of `coin_out` required to accept a swap order.
+    CurrentPrice { maker: String, order_id: u64 },
+}
+
+/// Parameters of a swap order created with `CreateSwapOrders`.
+#[cw_serde]
+pub struct SwapOrderParams {
+    /// Coin to send.
+    pub coin_in: Coin,
+    /// Coin to received.
+    pub coin_out: Coin,
+    /// If not empty, are the only counterparties accepted in the swap.
+    #[serde(default)]
+    pub allowed_takers: Vec<String>,
+    /// If specified, root of the merkle tree of the addresses that can accept
+    /// the deal.
+    #[serde(default)]
+    pub taker_merkle_root: Option<HexBinary>,
+    /// If specified, only the members of the cw4 group can accept the deal.
+    #[serde(default)]
+    pub taker_group: Option<TakerGroupParams>,
+    /// If true, a cw721 receipt of the order is minted to the maker.
+    #[serde(default)]
+    pub mint_receipt: bool,
+    /// If specified, duration in seconds during which only the designated
+    /// takers can accept the deal.
+    #[serde(default)]
+    pub reserved_for: Option<u64>,
+    /// Duration in seconds after which the deal expires.
+    pub timeout: u64,
+}
+
+/// Reference to a cw4 group whose members can accept a swap order.
+#[cw_serde]
+pub struct TakerGroupParams {
+    /// Address of the cw4 group contract.
+    pub group: String,
+    /// If specified, minimum weight of the member in the group.
+    pub min_weight: Option<u64>,
+}
+
+/// Update of the cw4 group of the market.
+#[cw_serde]
+pub enum TakerGroupUpdate {
+    /// Restricts the takers of all the orders to the members of the group.
+    Set(TakerGroupParams),
+    /// Allows anyone to accept the orders of the market.
+    Remove {},
+}
+
+/// Update of the compliance hook of the market.
+#[cw_serde]
+pub enum ComplianceHookUpdate {
+    /// Screens makers and takers with the contract at the given address.
+    Set(String),
+    /// Skips the screening.
+    Remove {},
+}
+
+/// Update of the reservation policy of the market.
+#[cw_serde]
+pub enum ReservationPolicyUpdate {
+    /// Allows takers to reserve the orders with the given policy.
+    Set(ReservationPolicy),
+    /// Disables new reservations.
+    Remove {},
+}
+
+/// Update of the rule suspending the makers failing too many settlements.
+#[cw_serde]
+pub enum SuspensionRuleUpdate {
+    /// Blocks new orders from the makers breaking the rule.
+    Set(SuspensionRule),
+    /// Allows all makers to create orders.
+    Remove {},
+}
+
+/// Update of the cw721 contract minting the order receipts.
+#[cw_serde]
+pub enum ReceiptContractUpdate {
+    /// Mints the receipts with the contract at the given address.
+    Set(String),
+    /// Disables the receipts of the new orders.
+    Remove {},
+}
+
+/// Query message sent to the compliance hook. The hook has to answer with an
+/// `IsAllowedResponse`.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum ComplianceQueryMsg {
+    #[returns(IsAllowedResponse)]
+    /// Returns if `address` can perform `action` in the market.
+    IsAllowed {
+        address: String,
+        action: ComplianceAction,
+    },
+}
+
+/// Action screened by the compliance hook.
+#[cw_serde]
+pub enum ComplianceAction {
+    /// Creation of a swap order by its maker.
+    CreateSwapOrder,
+    /// Acceptance of a swap order by its taker.
+    AcceptSwapOrder,
+}
+
+impl std::fmt::Display for ComplianceAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComplianceAction::CreateSwapOrder => write!(f, "create_swap_order"),
+            ComplianceAction::AcceptSwapOrder => write!(f, "accept_swap_order"),
+        }
+    }
+}
+
+/// Data structure returned by the compliance hook for the `IsAllowed` query.
+#[cw_serde]
+pub struct IsAllowedResponse {
+    pub allowed: bool,
+}
+
+/// Describes how `AcceptSwapOrders` handles orders that cannot be accepted
+/// or settled.
+#[cw_serde]
+pub enum AcceptMode {
+    /// The message fails if any of the orders fails.
+    AllOrNothing,
+    /// Orders that cannot be accepted are skipped and failed settlements are
+    /// handled by the failure policy of the contract.
+    BestEffort,
+}
+
+/// Data structure returned in the data of the `AcceptSwapOrders` response.
+#[cw_serde]
+pub struct AcceptSwapOrdersResponse {
+    pub results: Vec<AcceptSwapOrderResult>,
+}
+
+/// Result of the acceptance of an order in `AcceptSwapOrders`.
+#[cw_serde]
+pub struct AcceptSwapOrderResult {
+    pub maker: String,
+    pub order_id: u64,
+    /// Error that prevented the order from being accepted, if any.
+    pub error: Option<String>,
+}
+
+/// Swap order returned by the queries, with its status and phase at the
+/// current block time.
+#[cw_serde]
+pub struct SwapOrderResponse {
+    pub order: SwapOrder,
+    pub phase: OrderPhase,
 }
 
 /// Data structure returned from the `AllSwapOrders` query.
 #[cw_serde]
 pub struct AllSwapOrdersResponse {
-    pub orders: Vec<((Addr, u64), SwapOrder)>,
+    pub orders: Vec<((Addr, u64), SwapOrderResponse)>,
 }
 
 /// Data structure returned from the `SwapOrdersByMaker` query.
 #[cw_serde]
 pub struct SwapOrdersByMakerResponse {
-    pub orders: Vec<(u64, SwapOrder)>,
+    pub orders: Vec<(u64, SwapOrderResponse)>,
+}
+
+/// Data structure returned from the `HtlcOrdersByMaker` query.
+#[cw_serde]
+pub struct HtlcOrdersByMakerResponse {
+    pub orders: Vec<(u64, HtlcOrder)>,
+}
+
+/// Data structure returned from the `MakerStats` query.
+#[cw_serde]
+pub struct MakerStatsResponse {
+    pub stats: MakerStats,
+    pub suspended: bool,
+}
+
+/// Data structure returned from the `MakerBond` query.
+#[cw_serde]
+pub struct MakerBondResponse {
+    pub bond: Vec<Coin>,
+    pub order_bonds: Vec<(u64, Coin)>,
+}
+
+/// Data structure returned from the `MakerExposure` query.
+#[cw_serde]
+pub struct MakerExposureResponse {
+    pub exposure: Vec<Coin>,
+}
+
+/// Data structure returned from the `BlockedTakers` query.
+#[cw_serde]
+pub struct BlockedTakersResponse {
+    pub takers: Vec<Addr>,
+}
+
+/// Data structure returned from the `Operators` query.
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<(Addr, OperatorApproval)>,
+}
+
+/// Data structure returned from the `OpenQuoteRequests` query.
+#[cw_serde]
+pub struct QuoteRequestsResponse {
+    pub requests: Vec<(u64, QuoteRequest)>,
+}
+
+/// Data structure returned from the `QuotesByRequest` query.
+#[cw_serde]
+pub struct QuotesByRequestResponse {
+    pub quotes: Vec<((Addr, u64), SwapOrderResponse)>,
+}
+
+/// Data structure returned from the `CounterOffers` query.
+#[cw_serde]
+pub struct CounterOffersResponse {
+    pub offers: Vec<(Addr, CounterOffer)>,
+}
+
+/// Data structure returned from the `CurrentPrice` query.
+#[cw_serde]
+pub struct CurrentPriceResponse {
+    pub price: Coin,
 }
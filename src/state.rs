@@ -1,8 +1,8 @@
 use std::fmt;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, StdResult, Storage};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, BankMsg, Coin, Decimal, HexBinary, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
 
 /// This struct contains configuration parameters for the atomic swap market.
 #[cw_serde]
@@ -10,6 +10,122 @@ pub struct Config {
     /// Address of the contract owner. This is the only address
     /// that can modify the `Config`.
     pub owner: Addr,
+    /// If true, the creation of an order requires the maker balance to
+    /// cover all the open orders selling the same denom.
+    pub check_maker_balance: bool,
+    /// Action taken when the settlement of an accepted order fails.
+    pub failure_policy: FailurePolicy,
+    /// If specified, only the members of the group can accept the orders of
+    /// the market.
+    pub taker_group: Option<TakerGroup>,
+    /// If specified, contract queried to screen makers and takers. See
+    /// `ComplianceQueryMsg`.
+    pub compliance_hook: Option<Addr>,
+    /// If specified, cw721 contract minting the receipts of the orders. The
+    /// market has to be the minter of the contract.
+    pub receipt_contract: Option<Addr>,
+    /// If specified, takers can reserve the orders of the market.
+    pub reservation_policy: Option<ReservationPolicy>,
+    /// If specified, makers failing too many settlements cannot create orders.
+    pub suspension_rule: Option<SuspensionRule>,
+}
+
+/// Reference to a cw4 group whose members are allowed to accept an order.
+#[cw_serde]
+pub struct TakerGroup {
+    /// Address of the cw4 group contract.
+    pub group: Addr,
+    /// If specified, minimum weight of the member in the group.
+    pub min_weight: Option<u64>,
+}
+
+/// Parameters of the reservations of the swap orders.
+#[cw_serde]
+pub struct ReservationPolicy {
+    /// Deposit escrowed by the taker to reserve an order.
+    pub deposit: Coin,
+    /// Maximum duration of a reservation in seconds.
+    pub max_duration: u64,
+}
+
+/// Rule suspending the makers whose settlements fail too often.
+#[cw_serde]
+pub struct SuspensionRule {
+    /// Maximum ratio of failed settlements in the window.
+    pub max_failure_rate: Decimal,
+    /// Minimum number of settlements in the window for the rule to apply.
+    pub min_settlements: u64,
+    /// Duration of the window in seconds.
+    pub window: u64,
+}
+
+/// Counters of the orders of a maker.
+#[cw_serde]
+#[derive(Default)]
+pub struct MakerStats {
+    /// Number of orders created.
+    pub created: u64,
+    /// Number of orders confirmed.
+    pub confirmed: u64,
+    /// Number of orders whose settlement failed.
+    pub failed: u64,
+    /// Number of orders cancelled.
+    pub cancelled: u64,
+    /// Timestamp in seconds of the start of the current window of the
+    /// suspension rule.
+    pub window_start: u64,
+    /// Number of orders confirmed in the current window.
+    pub window_confirmed: u64,
+    /// Number of orders whose settlement failed in the current window.
+    pub window_failed: u64,
+}
+
+impl MakerStats {
+    /// Records the outcome of a settlement. A new window is started if the
+    /// current one ended.
+    pub fn record_settlement(&mut self, confirmed: bool, block_time: u64, window: Option<u64>) {
+        if let Some(window) = window {
+            if self.window_start.saturating_add(window) <= block_time {
+                self.window_start = block_time;
+                self.window_confirmed = 0;
+                self.window_failed = 0;
+            }
+        }
+        if confirmed {
+            self.confirmed += 1;
+            self.window_confirmed += 1;
+        } else {
+            self.failed += 1;
+            self.window_failed += 1;
+        }
+    }
+
+    /// Returns true if the failure rate of the current window is above the
+    /// one allowed by the rule.
+    pub fn is_suspended(&self, rule: &SuspensionRule, block_time: u64) -> bool {
+        if self.window_start.saturating_add(rule.window) <= block_time {
+            return false;
+        }
+        let settlements = self.window_confirmed + self.window_failed;
+        settlements > 0
+            && settlements >= rule.min_settlements
+            && Decimal::from_ratio(self.window_failed, settlements) > rule.max_failure_rate
+    }
+}
+
+/// Describes how the contract handles a swap order whose settlement through
+/// `x/authz` failed. The taker is always refunded.
+#[cw_serde]
+#[derive(Default)]
+pub enum FailurePolicy {
+    /// The order is closed with a `Failed` status.
+    #[default]
+    RefundAndClose,
+    /// The order is opened again and can be accepted by another taker.
+    RefundAndReopen,
+    /// The order is closed with a `Failed` status and the taker receives
+    /// `penalty` from the maker bond, or the whole bond if lower.
+    RefundAndPenalize { penalty: Coin },
 }
 
 /// Contains all information of an order.
@@ -19,14 +135,211 @@ pub struct SwapOrder {
     pub coin_in: Coin,
     /// Coin that the user wants to receive.
     pub coin_out: Coin,
-    /// Only address that can accept the deal.
-    /// If None, it is an open order. When matched,
-    /// it is equal to the taker address.
+    /// Addresses that can accept the deal, at most `MAX_ALLOWED_TAKERS`.
+    /// If empty, anyone can accept the order.
+    pub allowed_takers: Vec<Addr>,
+    /// If specified, root of the merkle tree of the addresses that can accept
+    /// the deal in addition to `allowed_takers`.
+    pub taker_merkle_root: Option<HexBinary>,
+    /// If specified, the taker has to be a member of the group in addition to
+    /// be allowed by `allowed_takers` or `taker_merkle_root`.
+    pub taker_group: Option<TakerGroup>,
+    /// Address that accepted the order. None while the order is open.
     pub taker: Option<Addr>,
     /// Timestamp after which the deal expires in seconds.
     pub timeout: u64,
     /// Status of the swap order.
     pub status: OrderStatus,
+    /// Error returned by the settlement through `x/authz`, truncated to
+    /// `MAX_FAILURE_REASON_LENGTH` characters. Set only if the order failed.
+    pub failure_reason: Option<String>,
+    /// Describes how the amount of `coin_out` required to accept the order is
+    /// computed. Once accepted, `coin_out` is the amount paid by the taker.
+    pub kind: OrderKind,
+    /// If specified, cw721 contract of the receipt of the order, whose token
+    /// identifier is the order identifier. The proceeds of the order are sent
+    /// to the holder of the receipt.
+    pub receipt: Option<Addr>,
+    /// Reservation of the order. It is kept after the end of the reservation
+    /// until its deposit is released.
+    pub reservation: Option<Reservation>,
+    /// If specified, timestamp in seconds until which only the designated
+    /// takers can accept the order.
+    pub reserved_until: Option<u64>,
+    /// If specified, identifier of the quote request answered by the order,
+    /// which can be accepted only through the request.
+    pub quote_request: Option<u64>,
+}
+
+impl SwapOrder {
+    /// Returns the status of the order at `block_time`. A reserved order is
+    /// open again once its reservation ended.
+    pub fn current_status(&self, block_time: u64) -> OrderStatus {
+        match &self.reservation {
+            Some(reservation)
+                if self.status == OrderStatus::Reserved && reservation.expiration < block_time =>
+            {
+                OrderStatus::Open
+            }
+            _ => self.status.clone(),
+        }
+    }
+
+    /// Returns whether the order is an auction with bids, which has to be
+    /// settled even after its timeout.
+    pub fn has_bids(&self) -> bool {
+        matches!(
+            self.kind,
+            OrderKind::English {
+                bidder: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Returns the phase of the order at `block_time`. An order without
+    /// designated takers is always public.
+    pub fn current_phase(&self, block_time: u64) -> OrderPhase {
+        let has_designated_takers = !self.allowed_takers.is_empty()
+            || self.taker_merkle_root.is_some()
+            || self.taker_group.is_some();
+        match self.reserved_until {
+            _ if !has_designated_takers => OrderPhase::Public,
+            Some(reserved_until) if reserved_until <= block_time => OrderPhase::Public,
+            _ => OrderPhase::Private,
+        }
+    }
+}
+
+/// Describes who can accept a swap order.
+#[cw_serde]
+pub enum OrderPhase {
+    /// Only the designated takers can accept the order.
+    Private,
+    /// Anyone can accept the order.
+    Public,
+}
+
+/// Reservation of a swap order by a taker.
+#[cw_serde]
+pub struct Reservation {
+    /// Only address that can accept the order until `expiration`.
+    pub taker: Addr,
+    /// Deposit escrowed in the contract. It is refunded if the taker accepts
+    /// the order before `expiration` and added to the maker bond otherwise.
+    pub deposit: Coin,
+    /// Timestamp after which the reservation ends in seconds.
+    pub expiration: u64,
+}
+
+/// Pricing of a swap order.
+#[cw_serde]
+pub enum OrderKind {
+    /// The taker pays exactly `coin_out`.
+    Fixed,
+    /// The amount of `coin_out` decreases linearly from `start_amount` at
+    /// `start_time` to `end_amount` at the order timeout.
+    Dutch {
+        start_amount: Uint128,
+        end_amount: Uint128,
+        /// Creation time of the order in seconds.
+        start_time: u64,
+        /// If specified, the price decreases only every `step` seconds.
+        step: Option<u64>,
+    },
+    /// Takers bid for `coin_in` until the order timeout and the highest bid is
+    /// escrowed in the contract. `coin_out` is the highest bid, or the reserve
+    /// price if there are no bids.
+    English {
+        /// Minimum amount of `coin_out` of the first bid.
+        reserve_price: Uint128,
+        /// Minimum amount by which a bid has to exceed the highest one.
+        min_increment: Uint128,
+        /// If specified, a bid placed less than `extension` seconds before the
+        /// timeout extends the auction to `extension` seconds after the bid.
+        extension: Option<u64>,
+        /// Address of the highest bidder.
+        bidder: Option<Addr>,
+    },
+}
+
+/// Contains all information of a hash time-locked order. Differently from
+/// a `SwapOrder`, the taker escrows its funds in the contract and the maker
+/// completes the swap by revealing the preimage of the hash lock.
+#[cw_serde]
+pub struct HtlcOrder {
+    /// Coin that the user wants to swap.
+    pub coin_in: Coin,
+    /// Coin that the user wants to receive.
+    pub coin_out: Coin,
+    /// Only address that can lock the deal.
+    /// If None, it is an open order. When locked,
+    /// it is equal to the taker address.
+    pub taker: Option<Addr>,
+    /// Sha256 hash of the secret known by the maker.
+    pub hash_lock: HexBinary,
+    /// Duration in seconds during which the taker funds stay locked.
+    pub time_lock: u64,
+    /// Timestamp after which the deal cannot be locked anymore in seconds.
+    pub timeout: u64,
+    /// Timestamp after which locked funds can be refunded in seconds.
+    /// Set when the taker locks the order.
+    pub lock_expiration: Option<u64>,
+    /// Secret revealed by the maker to claim the locked funds.
+    pub preimage: Option<HexBinary>,
+    /// Status of the hash time-locked order.
+    pub status: OrderStatus,
+}
+
+/// Offer of a taker to accept a swap order for a different `coin_out`. The
+/// offered coin is escrowed in the contract.
+#[cw_serde]
+pub struct CounterOffer {
+    /// Coin offered in place of the order `coin_out`.
+    pub coin: Coin,
+    /// Timestamp after which the offer cannot be accepted in seconds.
+    pub expiry: u64,
+}
+
+/// Approval given by a maker to an operator managing its swap orders. The
+/// maker stays the owner of the orders and the granter of `x/authz`.
+#[cw_serde]
+pub struct OperatorApproval {
+    /// Actions the operator can perform on behalf of the maker.
+    pub permissions: Vec<OperatorPermission>,
+    /// If specified, timestamp after which the approval expires in seconds.
+    pub expires: Option<u64>,
+}
+
+/// Action that an operator can perform on the swap orders of a maker.
+#[cw_serde]
+pub enum OperatorPermission {
+    /// Create swap orders with `CreateSwapOrder`, `CreateSwapOrders`,
+    /// `CreateDutchSwapOrder` and `CreateAuctionSwapOrder`, and quotes with
+    /// `SubmitQuote`.
+    CreateOrders,
+    /// Amend open swap orders with `AmendSwapOrder`.
+    AmendOrders,
+    /// Cancel open swap orders with `CancelSwapOrders` and `CancelAllMyOrders`.
+    CancelOrders,
+}
+
+/// Contains all information of a request for quote. Market makers respond
+/// with swap orders reserved to the requester.
+#[cw_serde]
+pub struct QuoteRequest {
+    /// Address of the user asking for quotes.
+    pub requester: Addr,
+    /// Coin that the requester wants to receive.
+    pub want: Coin,
+    /// Denom that the requester offers in exchange.
+    pub offer_denom: String,
+    /// Timestamp after which quotes cannot be submitted or accepted in seconds.
+    pub expiry: u64,
+    /// Number of quotes submitted.
+    pub quotes: u32,
+    /// Status of the request. `Accepted` once a quote has been accepted.
+    pub status: OrderStatus,
 }
 
 /// Status of a registered order.
@@ -34,6 +347,8 @@ pub struct SwapOrder {
 pub enum OrderStatus {
     /// Order created and open to be matched.
     Open,
+    /// Order reserved by a taker, open again at the end of the reservation.
+    Reserved,
     /// Order Accepted.
     Accepted,
     /// Order confirmed and concluded.
@@ -42,16 +357,42 @@ pub enum OrderStatus {
     Deleted,
     /// Order failed to be executed.
     Failed,
+    /// Locked funds returned after the time lock expired.
+    Refunded,
 }
 
 impl fmt::Display for OrderStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             OrderStatus::Open => write!(f, "Open"),
+            OrderStatus::Reserved => write!(f, "Reserved"),
             OrderStatus::Accepted => write!(f, "Accepted"),
             OrderStatus::Confirmed => write!(f, "Confirmed"),
             OrderStatus::Deleted => write!(f, "Deleted"),
             OrderStatus::Failed => write!(f, "Failed"),
+            OrderStatus::Refunded => write!(f, "Refunded"),
+        }
+    }
+}
+
+impl fmt::Display for FailurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailurePolicy::RefundAndClose => write!(f, "RefundAndClose"),
+            FailurePolicy::RefundAndReopen => write!(f, "RefundAndReopen"),
+            FailurePolicy::RefundAndPenalize { penalty } => {
+                write!(f, "RefundAndPenalize({penalty})")
+            }
+        }
+    }
+}
+
+impl fmt::Display for OperatorPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperatorPermission::CreateOrders => write!(f, "create_orders"),
+            OperatorPermission::AmendOrders => write!(f, "amend_orders"),
+            OperatorPermission::CancelOrders => write!(f, "cancel_orders"),
         }
     }
 }
@@ -63,9 +404,243 @@ pub fn next_id(store: &mut dyn Storage) -> StdResult<u64> {
     Ok(id)
 }
 
+/// Remove the counter-offers of an order satisfying `predicate` and return the
+/// messages to refund them.
+pub fn refund_counter_offers(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order_id: u64,
+    predicate: impl Fn(&CounterOffer) -> bool,
+) -> StdResult<Vec<BankMsg>> {
+    let offers = COUNTER_OFFERS
+        .prefix((maker, order_id))
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, CounterOffer)>>>()?;
+
+    let mut msgs = vec![];
+    for (taker, offer) in offers.into_iter().filter(|(_, offer)| predicate(offer)) {
+        COUNTER_OFFERS.remove(store, (maker, order_id, &taker));
+        msgs.push(BankMsg::Send {
+            to_address: taker.into_string(),
+            amount: vec![offer.coin],
+        });
+    }
+    Ok(msgs)
+}
+
+/// Remove the reservation of an order, if any, and reopen the order. Returns
+/// the message refunding the deposit to the taker if the reservation is not
+/// ended, otherwise the deposit is added to the maker bond.
+pub fn release_reservation(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order: &mut SwapOrder,
+    block_time: u64,
+) -> StdResult<Option<BankMsg>> {
+    let Some(reservation) = order.reservation.take() else {
+        return Ok(None);
+    };
+    if order.status == OrderStatus::Reserved {
+        order.status = OrderStatus::Open;
+    }
+
+    if reservation.expiration >= block_time {
+        return Ok(Some(BankMsg::Send {
+            to_address: reservation.taker.into_string(),
+            amount: vec![reservation.deposit],
+        }));
+    }
+    MAKER_BONDS.update(
+        store,
+        (maker, &reservation.deposit.denom),
+        |amount| -> StdResult<_> {
+            Ok(amount
+                .unwrap_or_default()
+                .checked_add(reservation.deposit.amount)?)
+        },
+    )?;
+    Ok(None)
+}
+
+/// Retrieve the identifier of the next quote request and increment the counter by one.
+pub fn next_quote_request_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id = QUOTE_REQUEST_COUNTER.may_load(store)?.unwrap_or_default();
+    QUOTE_REQUEST_COUNTER.save(store, &(id + 1))?;
+    Ok(id)
+}
+
+/// Retrieve the reply identifier of the next settlement submessage and increment
+/// the counter by one.
+pub fn next_reply_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id = REPLY_COUNTER.may_load(store)?.unwrap_or_default();
+    REPLY_COUNTER.save(store, &(id + 1))?;
+    Ok(id)
+}
+
+/// Applies `update` to the stats of `maker`.
+pub fn update_maker_stats(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    update: impl FnOnce(&mut MakerStats),
+) -> StdResult<()> {
+    let mut stats = MAKER_STATS.may_load(store, maker)?.unwrap_or_default();
+    update(&mut stats);
+    MAKER_STATS.save(store, maker, &stats)
+}
+
+/// Slash up to `penalty` from the bond of the order and then from the maker
+/// bond. Returns the slashed coin.
+pub fn slash_bonds(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order_id: u64,
+    penalty: &Coin,
+) -> StdResult<Coin> {
+    let mut slashed = Uint128::zero();
+
+    if let Some(mut bond) = ORDER_BONDS.may_load(store, (maker, order_id))? {
+        if bond.denom == penalty.denom {
+            let amount = bond.amount.min(penalty.amount);
+            bond.amount -= amount;
+            slashed += amount;
+            if bond.amount.is_zero() {
+                ORDER_BONDS.remove(store, (maker, order_id));
+            } else {
+                ORDER_BONDS.save(store, (maker, order_id), &bond)?;
+            }
+        }
+    }
+
+    let bond = MAKER_BONDS
+        .may_load(store, (maker, &penalty.denom))?
+        .unwrap_or_default();
+    let amount = bond.min(penalty.amount - slashed);
+    if bond == amount {
+        MAKER_BONDS.remove(store, (maker, &penalty.denom));
+    } else {
+        MAKER_BONDS.save(store, (maker, &penalty.denom), &(bond - amount))?;
+    }
+    slashed += amount;
+
+    Ok(Coin {
+        denom: penalty.denom.clone(),
+        amount: slashed,
+    })
+}
+
+/// Add the `coin_in` of an open order to the maker exposure. The order is
+/// indexed by its timeout to release the exposure once expired.
+pub fn commit_exposure(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order_id: u64,
+    order: &SwapOrder,
+) -> StdResult<()> {
+    MAKER_EXPOSURE.update(
+        store,
+        (maker, &order.coin_in.denom),
+        |exposure| -> StdResult<_> {
+            Ok(exposure
+                .unwrap_or_default()
+                .checked_add(order.coin_in.amount)?)
+        },
+    )?;
+    COMMITTED_ORDERS.save(store, (maker, order.timeout, order_id), &())
+}
+
+/// Remove the `coin_in` of an order from the maker exposure. Orders whose
+/// exposure has already been released are ignored.
+pub fn release_exposure(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order_id: u64,
+    order: &SwapOrder,
+) -> StdResult<()> {
+    let key = (maker, order.timeout, order_id);
+    if !COMMITTED_ORDERS.has(store, key) {
+        return Ok(());
+    }
+    COMMITTED_ORDERS.remove(store, key);
+
+    let exposure_key = (maker, order.coin_in.denom.as_str());
+    let exposure = MAKER_EXPOSURE
+        .load(store, exposure_key)?
+        .checked_sub(order.coin_in.amount)?;
+    if exposure.is_zero() {
+        MAKER_EXPOSURE.remove(store, exposure_key);
+    } else {
+        MAKER_EXPOSURE.save(store, exposure_key, &exposure)?;
+    }
+    Ok(())
+}
+
+/// Add a fixed price order to the order book. Other orders and quotes cannot
+/// be crossed.
+pub fn add_to_order_book(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    order_id: u64,
+    order: &SwapOrder,
+) -> StdResult<()> {
+    if order.kind != OrderKind::Fixed || order.quote_request.is_some() {
+        return Ok(());
+    }
+    ORDER_BOOK.save(store, order_book_key(order_id, order), maker)
+}
+
+/// Remove an order from the order book, if indexed with its current coins.
+pub fn remove_from_order_book(store: &mut dyn Storage, order_id: u64, order: &SwapOrder) {
+    ORDER_BOOK.remove(store, order_book_key(order_id, order));
+}
+
+fn order_book_key(order_id: u64, order: &SwapOrder) -> OrderBookKey<'_> {
+    (
+        (&order.coin_in.denom, &order.coin_out.denom),
+        order.coin_in.amount.u128(),
+        (order.coin_out.amount.u128(), order_id),
+    )
+}
+
+/// Release the exposure of all the maker orders expired before `block_time`.
+pub fn release_expired_exposure(
+    store: &mut dyn Storage,
+    maker: &Addr,
+    block_time: u64,
+) -> StdResult<()> {
+    for (_, order_id) in expired_committed_orders(store, maker, block_time)? {
+        let order = SWAP_ORDERS.load(store, (maker, order_id))?;
+        release_exposure(store, maker, order_id, &order)?;
+    }
+    Ok(())
+}
+
+/// Returns timeout and identifier of the maker orders still counted in the
+/// exposure but expired before `block_time`. Auctions with bids stay committed
+/// until they are settled.
+pub fn expired_committed_orders(
+    store: &dyn Storage,
+    maker: &Addr,
+    block_time: u64,
+) -> StdResult<Vec<(u64, u64)>> {
+    let mut expired = vec![];
+    for item in COMMITTED_ORDERS.sub_prefix(maker).keys(
+        store,
+        None,
+        Some(Bound::exclusive((block_time, 0))),
+        Order::Ascending,
+    ) {
+        let (timeout, order_id) = item?;
+        if !SWAP_ORDERS.load(store, (maker, order_id))?.has_bids() {
+            expired.push((timeout, order_id));
+        }
+    }
+    Ok(expired)
+}
+
 /// Temporary structure used to store the order that has been
-/// confirmed and is waiting to be accepted through `x/authz`
-/// message.
+/// accepted and is waiting to be confirmed through `x/authz`
+/// message. It is stored under the identifier of the reply of the
+/// settlement submessage and removed when the reply is handled.
 #[cw_serde]
 pub struct OrderPointer {
     /// Identifier of the order to be accepted.
@@ -78,9 +653,60 @@ pub struct OrderPointer {
 
 /// Data structure used to store the number of created deals.
 pub const COUNTER: Item<u64> = Item::new("counter");
-/// Data structure to store the temporary data of the order being confirmed.
-pub const ORDER_POINTER: Item<OrderPointer> = Item::new("order_pointer");
+/// Data structure used to store the number of sent settlement submessages.
+pub const REPLY_COUNTER: Item<u64> = Item::new("reply_counter");
+/// Data structure to store the temporary data of the orders being confirmed
+/// indexed by the reply identifier of the settlement submessage. A single
+/// submessage confirms all the orders of a maker accepted in one message.
+pub const PENDING_SETTLEMENTS: Map<u64, Vec<OrderPointer>> = Map::new("pending_settlements");
+/// Data structure to store the temporary data of two crossed orders being
+/// settled, indexed by the reply identifier of the settlement submessage. The
+/// taker of each pointer is the maker of the other order.
+pub const PENDING_MATCHES: Map<u64, Vec<OrderPointer>> = Map::new("pending_matches");
+/// Data structure used to store, for each crossed order being settled, the maker
+/// and the identifier of the opposite order.
+pub const MATCHED_ORDERS: Map<(&Addr, u64), (Addr, u64)> = Map::new("matched_orders");
+/// Data structure used to index the fixed price orders by `coin_in` and `coin_out`
+/// denoms, `coin_in` amount, `coin_out` amount and identifier, to find crossing
+/// orders from the cheapest one. Entries of orders that are no longer open are
+/// removed when found while crossing orders.
+pub const ORDER_BOOK: Map<OrderBookKey, Addr> = Map::new("order_book");
+/// Key of the order book: the `coin_in` and `coin_out` denoms, the `coin_in`
+/// amount, and the `coin_out` amount with the order identifier.
+pub type OrderBookKey<'a> = ((&'a str, &'a str), u128, (u128, u64));
 /// Data structure that holds the contract configuration.
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Data structure used to store the amount of each denom committed by a
+/// maker in open orders.
+pub const MAKER_EXPOSURE: Map<(&Addr, &str), Uint128> = Map::new("maker_exposure");
+/// Data structure used to store the bond deposited by makers for each denom.
+pub const MAKER_BONDS: Map<(&Addr, &str), Uint128> = Map::new("maker_bonds");
+/// Data structure used to store the bond deposited by makers for an order.
+pub const ORDER_BONDS: Map<(&Addr, u64), Coin> = Map::new("order_bonds");
+/// Data structure used to store the order counters of each maker.
+pub const MAKER_STATS: Map<&Addr, MakerStats> = Map::new("maker_stats");
+/// Data structure used to store the takers that cannot accept the orders of a
+/// maker, by maker and taker.
+pub const BLOCKED_TAKERS: Map<(&Addr, &Addr), ()> = Map::new("blocked_takers");
+/// Maps a maker and an operator to the approval given by the maker.
+pub const OPERATORS: Map<(&Addr, &Addr), OperatorApproval> = Map::new("operators");
+/// Data structure used to index by timeout the orders counted in the maker exposure.
+pub const COMMITTED_ORDERS: Map<(&Addr, u64, u64), ()> = Map::new("committed_orders");
 /// Data strusture used to store all swap orders.
 pub const SWAP_ORDERS: Map<(&Addr, u64), SwapOrder> = Map::new("swap_orders");
+/// Data structure used to store the number of created quote requests.
+pub const QUOTE_REQUEST_COUNTER: Item<u64> = Item::new("quote_request_counter");
+/// Data structure used to store all quote requests.
+pub const QUOTE_REQUESTS: Map<u64, QuoteRequest> = Map::new("quote_requests");
+/// Data structure used to index the swap orders submitted as quotes by request
+/// identifier, maker and order identifier.
+pub const QUOTES: Map<(u64, &Addr, u64), ()> = Map::new("quotes");
+/// Data structure used to store the counter-offers by order maker, order
+/// identifier and taker.
+pub const COUNTER_OFFERS: Map<(&Addr, u64, &Addr), CounterOffer> = Map::new("counter_offers");
+/// Data structure used to store the coin of the counter-offer accepted for an
+/// order being settled, by order maker and identifier. The order keeps its own
+/// `coin_out` in case it is opened again.
+pub const ACCEPTED_OFFERS: Map<(&Addr, u64), Coin> = Map::new("accepted_offers");
+/// Data structure used to store all hash time-locked orders.
+pub const HTLC_ORDERS: Map<(&Addr, u64), HtlcOrder> = Map::new("htlc_orders");
@@ -0,0 +1,68 @@
+use cosmwasm_std::{
+    testing::{mock_env, mock_info, MockApi, MockStorage},
+    Coin, DepsMut, Env, OwnedDeps, Response,
+};
+
+use crate::contract::{execute, instantiate};
+use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::tests::mock_querier::{
+    contract_execution_grant, mock_dependencies_with_grants, AuthzMockQuerier,
+};
+
+/// Returns the dependencies of a market owned by `owner`, with a grant of
+/// `grant_limit` to the contract and the given account balances.
+pub fn mock_market(
+    grant_limit: &[Coin],
+    balances: &[(&str, &[Coin])],
+) -> OwnedDeps<MockStorage, MockApi, AuthzMockQuerier> {
+    let env = mock_env();
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        grant_limit,
+        None,
+    )));
+    for (address, balance) in balances {
+        deps.querier.base.update_balance(*address, balance.to_vec());
+    }
+    instantiate(
+        deps.as_mut(),
+        env,
+        mock_info("owner", &[]),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+    deps
+}
+
+/// Returns the message creating a public fixed price order.
+pub fn create_order_msg(coin_in: Coin, coin_out: Coin, timeout: u64) -> ExecuteMsg {
+    ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in,
+        coin_out,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout,
+    }
+}
+
+/// Creates a public fixed price order of `maker`.
+pub fn create_order(
+    deps: DepsMut,
+    env: &Env,
+    maker: &str,
+    coin_in: Coin,
+    coin_out: Coin,
+    timeout: u64,
+) -> Response {
+    execute(
+        deps,
+        env.clone(),
+        mock_info(maker, &[]),
+        create_order_msg(coin_in, coin_out, timeout),
+    )
+    .unwrap()
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{MockApi, MockQuerier, MockStorage},
+    to_json_binary, Binary, Coin, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult,
+};
+use osmosis_std::types::cosmos::authz::v1beta1::QueryGrantsRequest;
+use osmosis_std::types::cosmwasm::wasm::v1::{
+    AllowAllMessagesFilter, ContractExecutionAuthorization, MaxFundsLimit,
+};
+use serde_json::json;
+
+pub const GRANTS_QUERY_PATH: &str = "/cosmos.authz.v1beta1.Query/Grants";
+
+/// Returns the JSON response of the `x/authz` `Grants` query containing a
+/// `ContractExecutionAuthorization` for `contract` with a `MaxFundsLimit`.
+pub fn contract_execution_grant(
+    contract: &str,
+    limit: &[Coin],
+    expiration: Option<&str>,
+) -> Binary {
+    to_json_binary(&json!({
+        "grants": [{
+            "authorization": {
+                "@type": ContractExecutionAuthorization::TYPE_URL,
+                "grants": [{
+                    "contract": contract,
+                    "limit": {
+                        "@type": MaxFundsLimit::TYPE_URL,
+                        "amounts": limit,
+                    },
+                    "filter": {
+                        "@type": AllowAllMessagesFilter::TYPE_URL,
+                    },
+                }],
+            },
+            "expiration": expiration,
+        }],
+        "pagination": null,
+    }))
+    .unwrap()
+}
+
+/// Mock querier that handles the `x/authz` `Grants` stargate query and
+/// forwards all other queries to the default `MockQuerier`.
+pub struct AuthzMockQuerier {
+    pub base: MockQuerier,
+    /// Response of the `Grants` query. If None, the query fails as the SDK
+    /// does when no authorization is found.
+    pub grants: Option<Binary>,
+}
+
+impl Querier for AuthzMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
+            Ok(request) => request,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("parsing query request: {e}"),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        match request {
+            QueryRequest::Stargate { path, .. } if path == GRANTS_QUERY_PATH => {
+                match &self.grants {
+                    Some(grants) => SystemResult::Ok(ContractResult::Ok(grants.clone())),
+                    None => {
+                        SystemResult::Ok(ContractResult::Err("authorization not found".to_string()))
+                    }
+                }
+            }
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+/// Creates mock dependencies whose querier answers the `x/authz` `Grants` query
+/// with `grants`.
+pub fn mock_dependencies_with_grants(
+    grants: Option<Binary>,
+) -> OwnedDeps<MockStorage, MockApi, AuthzMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: AuthzMockQuerier {
+            base: MockQuerier::default(),
+            grants,
+        },
+        custom_query_type: Default::default(),
+    }
+}
+
+/// Decodes the grantee of a `Grants` stargate query.
+pub fn grants_query_grantee(data: &Binary) -> String {
+    <QueryGrantsRequest as prost::Message>::decode(data.as_slice())
+        .unwrap()
+        .grantee
+}
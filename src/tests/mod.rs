@@ -1,3 +1,5 @@
+mod helpers;
+mod mock_querier;
 mod multitest;
 mod testtube;
 mod unit_test;
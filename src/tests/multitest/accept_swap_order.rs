@@ -1,24 +1,11 @@
-use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-use cw_multi_test::{AppBuilder, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_multi_test::{AppBuilder, BankSudo, Executor, SudoMsg};
 
 use crate::msg::{ExecuteMsg, SwapOrdersByMakerResponse};
 use crate::msg::{InstantiateMsg, QueryMsg};
-use crate::state::{OrderStatus, SwapOrder};
+use crate::state::{OrderKind, OrderStatus, SwapOrder};
 use crate::tests::multitest::stargate_handler::CustomStargate;
-
-const OWNER: &str = "0xstepit000";
-
-// Creates a market contract.
-pub fn atomic_swap_market_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        crate::contract::execute,
-        crate::contract::instantiate,
-        crate::contract::query,
-    )
-    .with_reply_empty(crate::contract::reply);
-
-    Box::new(contract)
-}
+use crate::tests::multitest::{atomic_swap_market_contract, OWNER};
 
 #[test]
 fn test_accept_swap_order_no_taker() {
@@ -66,9 +53,14 @@ fn test_accept_swap_order_no_taker() {
 
     // Create first order.
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     app.execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -78,6 +70,7 @@ fn test_accept_swap_order_no_taker() {
     let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
         order_id: 0,
         maker: maker.to_string(),
+        proof: vec![],
     };
     app.execute_contract(
         stepit.clone(),
@@ -134,9 +127,14 @@ fn test_accept_swap_order_with_taker() {
 
     // Create first order.
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: Some(stepit.to_string()),
+        allowed_takers: vec![stepit.to_string()],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     app.execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -146,6 +144,7 @@ fn test_accept_swap_order_with_taker() {
     let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
         order_id: 0,
         maker: maker.to_string(),
+        proof: vec![],
     };
     app.execute_contract(
         stepit.clone(),
@@ -167,13 +166,22 @@ fn test_accept_swap_order_with_taker() {
 
     let current_block_time = app.block_info().time.seconds();
     assert_eq!(
-        resp.orders[0].1,
+        resp.orders[0].1.order,
         SwapOrder {
             coin_in: Coin::new(1_000, "uosmo"),
             coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![stepit.clone()],
+            taker_merkle_root: None,
+            taker_group: None,
             taker: Some(stepit),
             timeout: 10 + current_block_time,
             status: OrderStatus::Accepted,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
         },
         "expected the order to be accepted"
     )
@@ -225,9 +233,14 @@ fn test_accept_swap_order_trigger_reply() {
 
     // Create first order.
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: Some(stepit.to_string()),
+        allowed_takers: vec![stepit.to_string()],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     app.execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -237,6 +250,7 @@ fn test_accept_swap_order_trigger_reply() {
     let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
         order_id: 0,
         maker: maker.to_string(),
+        proof: vec![],
     };
 
     let mut current_block = app.block_info();
@@ -260,14 +274,28 @@ fn test_accept_swap_order_trigger_reply() {
             },
         )
         .unwrap();
+    let failure_reason = resp.orders[0].1.order.failure_reason.clone().unwrap();
+    assert!(
+        failure_reason.starts_with("Failed to use auhtz"),
+        "expected the submessage error as failure reason"
+    );
     assert_eq!(
-        resp.orders[0].1,
+        resp.orders[0].1.order,
         SwapOrder {
             coin_in: Coin::new(1_000, "uosmo"),
             coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![stepit.clone()],
+            taker_merkle_root: None,
+            taker_group: None,
             taker: Some(stepit),
             timeout: 10 + current_block_time,
             status: OrderStatus::Failed,
+            failure_reason: Some(failure_reason),
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
         },
         "expected the order to be failed because error in submessage"
     );
@@ -0,0 +1,179 @@
+use cosmwasm_std::{from_json, Addr, Coin};
+use cw_multi_test::Executor;
+
+use crate::msg::{AcceptMode, AcceptSwapOrdersResponse, ExecuteMsg};
+use crate::state::OrderStatus;
+use crate::tests::multitest::{
+    create_order, fail_settlements, order_params, query_orders, setup_market, MarketApp,
+};
+
+// Instantiates the market and creates one order for each maker.
+fn setup_orders(makers: &[&Addr], taker: &Addr) -> (MarketApp, Addr) {
+    let maker_funds = [Coin::new(1_000, "uosmo")];
+    let taker_funds = [Coin::new(3_000, "usdc")];
+    let mut balances = vec![(taker.as_str(), &taker_funds[..])];
+    balances.extend(
+        makers
+            .iter()
+            .map(|maker| (maker.as_str(), &maker_funds[..])),
+    );
+    let (mut app, market_addr) = setup_market(&balances);
+
+    for maker in makers {
+        create_order(&mut app, &market_addr, maker.as_str(), order_params()).unwrap();
+    }
+
+    (app, market_addr)
+}
+
+fn order_status(app: &MarketApp, market_addr: &Addr, maker: &Addr) -> OrderStatus {
+    query_orders(app, market_addr, maker.as_str())[0]
+        .1
+        .status
+        .clone()
+}
+
+#[test]
+fn test_accept_swap_orders_best_effort() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let taker = Addr::unchecked("0xstepit");
+    let (mut app, market_addr) = setup_orders(&[&alice, &bob], &taker);
+
+    // The order of bob is accepted twice and the second acceptance is skipped.
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![
+            (alice.to_string(), 0),
+            (bob.to_string(), 1),
+            (bob.to_string(), 1),
+        ],
+        mode: AcceptMode::BestEffort,
+        proofs: vec![],
+    };
+    let res = app
+        .execute_contract(
+            taker.clone(),
+            market_addr.clone(),
+            &accept_orders_msg,
+            &[Coin::new(3_000, "usdc")],
+        )
+        .unwrap();
+
+    let AcceptSwapOrdersResponse { results } = from_json(res.data.unwrap()).unwrap();
+    assert!(results[0].error.is_none());
+    assert!(results[1].error.is_none());
+    assert!(results[2].error.is_some());
+
+    assert_eq!(
+        order_status(&app, &market_addr, &alice),
+        OrderStatus::Accepted
+    );
+    assert_eq!(
+        order_status(&app, &market_addr, &bob),
+        OrderStatus::Accepted
+    );
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(1_000, "usdc"),
+        "expected unused funds to be refunded"
+    );
+}
+
+#[test]
+fn test_accept_swap_orders_best_effort_failed_settlement() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let taker = Addr::unchecked("0xstepit");
+    let (mut app, market_addr) = setup_orders(&[&alice, &bob], &taker);
+
+    // The custom stargate handler fails at height 1.
+    fail_settlements(&mut app, true);
+
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![(alice.to_string(), 0), (bob.to_string(), 1)],
+        mode: AcceptMode::BestEffort,
+        proofs: vec![],
+    };
+    app.execute_contract(
+        taker.clone(),
+        market_addr.clone(),
+        &accept_orders_msg,
+        &[Coin::new(2_000, "usdc")],
+    )
+    .unwrap();
+
+    assert_eq!(
+        order_status(&app, &market_addr, &alice),
+        OrderStatus::Failed
+    );
+    assert_eq!(order_status(&app, &market_addr, &bob), OrderStatus::Failed);
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(3_000, "usdc"),
+        "expected the taker to be refunded"
+    );
+}
+
+#[test]
+fn test_accept_swap_orders_all_or_nothing() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let taker = Addr::unchecked("0xstepit");
+    let (mut app, market_addr) = setup_orders(&[&alice, &bob], &taker);
+
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![
+            (alice.to_string(), 0),
+            (bob.to_string(), 1),
+            (bob.to_string(), 1),
+        ],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    app.execute_contract(
+        taker.clone(),
+        market_addr.clone(),
+        &accept_orders_msg,
+        &[Coin::new(3_000, "usdc")],
+    )
+    .unwrap_err();
+    assert_eq!(order_status(&app, &market_addr, &alice), OrderStatus::Open);
+
+    // A failed settlement reverts the whole message.
+    fail_settlements(&mut app, true);
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![(alice.to_string(), 0), (bob.to_string(), 1)],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    app.execute_contract(
+        taker.clone(),
+        market_addr.clone(),
+        &accept_orders_msg,
+        &[Coin::new(2_000, "usdc")],
+    )
+    .unwrap_err();
+    assert_eq!(order_status(&app, &market_addr, &alice), OrderStatus::Open);
+    assert_eq!(order_status(&app, &market_addr, &bob), OrderStatus::Open);
+
+    fail_settlements(&mut app, false);
+    app.execute_contract(
+        taker.clone(),
+        market_addr.clone(),
+        &accept_orders_msg,
+        &[Coin::new(2_000, "usdc")],
+    )
+    .unwrap();
+    assert_eq!(
+        order_status(&app, &market_addr, &alice),
+        OrderStatus::Accepted
+    );
+    assert_eq!(
+        order_status(&app, &market_addr, &bob),
+        OrderStatus::Accepted
+    );
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(1_000, "usdc")
+    );
+}
@@ -0,0 +1,310 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo,
+    Response, StdError, StdResult, Uint128,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ComplianceAction, ComplianceHookUpdate, ComplianceQueryMsg, ExecuteMsg, IsAllowedResponse,
+};
+use crate::tests::multitest::{
+    accept_order, create_order, execute_market, order_params, query_orders, setup_market,
+    update_config, ConfigUpdate, MarketApp, OWNER,
+};
+
+/// Reference compliance hook rejecting the addresses of a sanctions list.
+mod mock_compliance {
+    use super::*;
+
+    const SANCTIONED: Item<Vec<String>> = Item::new("sanctioned");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub sanctioned: Vec<String>,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        /// Replaces the sanctions list.
+        UpdateSanctioned { sanctioned: Vec<String> },
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        SANCTIONED.save(deps.storage, &msg.sanctioned)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        let ExecuteMsg::UpdateSanctioned { sanctioned } = msg;
+        SANCTIONED.save(deps.storage, &sanctioned)?;
+        Ok(Response::new())
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: ComplianceQueryMsg) -> StdResult<Binary> {
+        let ComplianceQueryMsg::IsAllowed { address, .. } = msg;
+        let sanctioned = SANCTIONED.load(deps.storage)?;
+        to_json_binary(&IsAllowedResponse {
+            allowed: !sanctioned.contains(&address),
+        })
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+}
+
+/// Hook that does not implement the compliance query.
+fn broken_compliance_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> {
+            Err(StdError::generic_err("unsupported query"))
+        },
+    ))
+}
+
+// Instantiates the market with a compliance hook sanctioning `sanctioned`.
+fn setup_market_with_hook() -> (MarketApp, Addr, Addr) {
+    let (mut app, market_addr) = setup_market(&[
+        (
+            "maker",
+            &[Coin::new(2_000, "uosmo"), Coin::new(2_000, "usdc")],
+        ),
+        (
+            "sanctioned",
+            &[Coin::new(2_000, "uosmo"), Coin::new(2_000, "usdc")],
+        ),
+        ("taker", &[Coin::new(2_000, "usdc")]),
+    ]);
+
+    let hook_id = app.store_code(mock_compliance::contract());
+    let hook_addr = app
+        .instantiate_contract(
+            hook_id,
+            Addr::unchecked(OWNER),
+            &mock_compliance::InstantiateMsg {
+                sanctioned: vec!["sanctioned".to_string()],
+            },
+            &[],
+            "compliance-hook",
+            None,
+        )
+        .unwrap();
+    set_hook(
+        &mut app,
+        &market_addr,
+        ComplianceHookUpdate::Set(hook_addr.to_string()),
+    );
+
+    (app, market_addr, hook_addr)
+}
+
+fn sanction(app: &mut MarketApp, hook_addr: &Addr, sanctioned: &[&str]) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        hook_addr.clone(),
+        &mock_compliance::ExecuteMsg::UpdateSanctioned {
+            sanctioned: sanctioned.iter().map(ToString::to_string).collect(),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn set_hook(app: &mut MarketApp, market_addr: &Addr, compliance_hook: ComplianceHookUpdate) {
+    let update = ConfigUpdate {
+        compliance_hook: Some(compliance_hook),
+        ..Default::default()
+    };
+    update_config(app, market_addr, update);
+}
+
+#[test]
+fn test_compliance_hook_screens_makers_and_takers() {
+    let (mut app, market_addr, hook_addr) = setup_market_with_hook();
+
+    let err = create_order(&mut app, &market_addr, "sanctioned", order_params()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AddressNotAllowed {
+            address: "sanctioned".to_string(),
+            action: ComplianceAction::CreateSwapOrder.to_string(),
+        }
+    );
+    create_order(&mut app, &market_addr, "maker", order_params()).unwrap();
+
+    // The sanctions list changes after the creation of the order.
+    sanction(&mut app, &hook_addr, &["taker"]);
+    let err = accept_order(&mut app, &market_addr, "taker", "maker", 0).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AddressNotAllowed {
+            address: "taker".to_string(),
+            action: ComplianceAction::AcceptSwapOrder.to_string(),
+        }
+    );
+    accept_order(&mut app, &market_addr, "sanctioned", "maker", 0).unwrap();
+}
+
+#[test]
+fn test_compliance_hook_fails_closed() {
+    let (mut app, market_addr, _) = setup_market_with_hook();
+    create_order(&mut app, &market_addr, "maker", order_params()).unwrap();
+
+    let broken_id = app.store_code(broken_compliance_contract());
+    let broken_addr = app
+        .instantiate_contract(
+            broken_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "broken-hook",
+            None,
+        )
+        .unwrap();
+    set_hook(
+        &mut app,
+        &market_addr,
+        ComplianceHookUpdate::Set(broken_addr.to_string()),
+    );
+
+    let err = create_order(&mut app, &market_addr, "maker", order_params()).unwrap_err();
+    assert!(
+        matches!(err, ContractError::ComplianceHookFailed { .. }),
+        "expected the creation to fail when the hook cannot be queried"
+    );
+    let err = accept_order(&mut app, &market_addr, "taker", "maker", 0).unwrap_err();
+    assert!(
+        matches!(err, ContractError::ComplianceHookFailed { .. }),
+        "expected the acceptance to fail when the hook cannot be queried"
+    );
+
+    set_hook(&mut app, &market_addr, ComplianceHookUpdate::Remove {});
+    accept_order(&mut app, &market_addr, "taker", "maker", 0).unwrap();
+}
+
+#[test]
+fn test_compliance_hook_screens_bidders() {
+    let (mut app, market_addr, hook_addr) = setup_market_with_hook();
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::one(),
+        extension: None,
+        timeout: 10,
+    };
+    execute_market(&mut app, "maker", &market_addr, &create_auction_msg, &[]).unwrap();
+    let bid_msg = ExecuteMsg::BidSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+
+    let err = execute_market(
+        &mut app,
+        "sanctioned",
+        &market_addr,
+        &bid_msg,
+        &[Coin::new(1_000, "usdc")],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AddressNotAllowed {
+            address: "sanctioned".to_string(),
+            action: ComplianceAction::AcceptSwapOrder.to_string(),
+        }
+    );
+    execute_market(
+        &mut app,
+        "taker",
+        &market_addr,
+        &bid_msg,
+        &[Coin::new(1_000, "usdc")],
+    )
+    .unwrap();
+
+    // The winner is sanctioned before the settlement: the bid is refunded.
+    sanction(&mut app, &hook_addr, &["taker"]);
+    app.update_block(|block| block.time = block.time.plus_seconds(11));
+    let settle_msg = ExecuteMsg::SettleAuction {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    execute_market(&mut app, OWNER, &market_addr, &settle_msg, &[]).unwrap();
+
+    assert!(query_orders(&app, &market_addr, "maker").is_empty());
+    let balance = app.wrap().query_balance("taker", "usdc").unwrap();
+    assert_eq!(balance, Coin::new(2_000, "usdc"));
+}
+
+#[test]
+fn test_compliance_hook_screens_htlc_parties() {
+    let (mut app, market_addr, hook_addr) = setup_market_with_hook();
+    let create_htlc_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: HexBinary::from(vec![0; 32]),
+        time_lock: 10,
+        timeout: 10,
+    };
+
+    let err =
+        execute_market(&mut app, "sanctioned", &market_addr, &create_htlc_msg, &[]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AddressNotAllowed {
+            address: "sanctioned".to_string(),
+            action: ComplianceAction::CreateSwapOrder.to_string(),
+        }
+    );
+    execute_market(&mut app, "maker", &market_addr, &create_htlc_msg, &[]).unwrap();
+
+    sanction(&mut app, &hook_addr, &["taker"]);
+    let lock_msg = ExecuteMsg::LockHtlcOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    let err = execute_market(
+        &mut app,
+        "taker",
+        &market_addr,
+        &lock_msg,
+        &[Coin::new(1_000, "usdc")],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AddressNotAllowed {
+            address: "taker".to_string(),
+            action: ComplianceAction::AcceptSwapOrder.to_string(),
+        }
+    );
+    execute_market(
+        &mut app,
+        "sanctioned",
+        &market_addr,
+        &lock_msg,
+        &[Coin::new(1_000, "usdc")],
+    )
+    .unwrap();
+}
@@ -3,7 +3,7 @@ use cw_multi_test::{AppBuilder, BankSudo, Contract, ContractWrapper, Executor, S
 
 use crate::msg::ExecuteMsg;
 use crate::msg::{AllSwapOrdersResponse, InstantiateMsg, QueryMsg};
-use crate::state::{OrderStatus, SwapOrder};
+use crate::state::{OrderKind, OrderStatus, SwapOrder};
 use crate::tests::multitest::stargate_handler::CustomStargate;
 
 const OWNER: &str = "0xstepit000";
@@ -66,9 +66,14 @@ fn test_confirm_swap_order() {
 
     // Create first order.
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     app.execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -78,6 +83,7 @@ fn test_confirm_swap_order() {
     let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
         order_id: 0,
         maker: maker.to_string(),
+        proof: vec![],
     };
     app.execute_contract(
         stepit.clone(),
@@ -94,13 +100,22 @@ fn test_confirm_swap_order() {
         .unwrap();
     assert_eq!(resp_all.orders.len(), 1, "expected one orders");
     assert_eq!(
-        resp_all.orders[0].1,
+        resp_all.orders[0].1.order,
         SwapOrder {
             coin_in: Coin::new(1_000, "uosmo"),
             coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![],
+            taker_merkle_root: None,
+            taker_group: None,
             taker: Some(stepit.clone()),
             status: OrderStatus::Accepted,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
             timeout: 10 + current_block_time,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
         },
         "expected a different order status"
     );
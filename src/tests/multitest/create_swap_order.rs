@@ -1,9 +1,10 @@
 use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-use cw_multi_test::{App, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+use cw_multi_test::{App, AppBuilder, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
 
 use crate::{
     error::ContractError,
     msg::{AllSwapOrdersResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SwapOrdersByMakerResponse},
+    tests::multitest::stargate_handler::CustomStargate,
 };
 
 pub fn atomic_swap_market_contract() -> Box<dyn Contract<Empty>> {
@@ -17,7 +18,9 @@ pub fn atomic_swap_market_contract() -> Box<dyn Contract<Empty>> {
 
 #[test]
 fn test_create_order() {
-    let mut app: App = App::default();
+    let mut app = AppBuilder::new()
+        .with_stargate(CustomStargate::default())
+        .build(|_, _, _| {});
 
     let owner = Addr::unchecked("0xowner".to_string());
     let stepit = Addr::unchecked("0xstepit".to_string());
@@ -57,9 +60,14 @@ fn test_create_order() {
 
     // Create first order
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     app.execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -67,9 +75,14 @@ fn test_create_order() {
 
     // Create second order with another account
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 100,
     };
     app.execute_contract(stepit.clone(), market_addr.clone(), &create_order_msg, &[])
@@ -122,7 +135,9 @@ fn test_create_order() {
 
 #[test]
 fn create_order_handle_errors() {
-    let mut app: App = App::default();
+    let mut app = AppBuilder::new()
+        .with_stargate(CustomStargate::default())
+        .build(|_, _, _| {});
 
     let owner = Addr::unchecked("owner".to_string());
     let maker = Addr::unchecked("maker".to_string());
@@ -161,9 +176,14 @@ fn create_order_handle_errors() {
     .unwrap();
 
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uosmo"),
         coin_out: Coin::new(1_000, "uosmo"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
     let err = app
@@ -178,3 +198,50 @@ fn create_order_handle_errors() {
         "expected error because sent two coins with same denom"
     );
 }
+
+#[test]
+fn create_order_without_grant() {
+    // Default app fails all stargate queries like a chain where the maker
+    // didn't grant any authorization to the contract.
+    let mut app: App = App::default();
+
+    let owner = Addr::unchecked("owner".to_string());
+    let maker = Addr::unchecked("maker".to_string());
+
+    // Store and instantiate the market contract.
+    let market_id = app.store_code(atomic_swap_market_contract());
+    let init_market_msg = InstantiateMsg {
+        owner: Some("owner".to_string()),
+    };
+    let market_addr = app
+        .instantiate_contract(
+            market_id,
+            owner.clone(),
+            &init_market_msg,
+            &[],
+            "atomic-swap-market",
+            None,
+        )
+        .unwrap();
+
+    let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    };
+    let err = app
+        .execute_contract(maker.clone(), market_addr.clone(), &create_order_msg, &[])
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::MissingAuthzGrant {},
+        "expected error because maker didn't grant the contract"
+    );
+}
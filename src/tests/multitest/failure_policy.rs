@@ -0,0 +1,194 @@
+use cosmwasm_std::{Addr, Coin, Event};
+use cw_multi_test::{AppResponse, Executor};
+
+use crate::msg::{ExecuteMsg, MakerBondResponse, QueryMsg};
+use crate::state::{FailurePolicy, OrderStatus, SwapOrder};
+use crate::tests::multitest::{
+    accept_order, create_order, fail_settlements, order_params, query_orders, setup_market,
+    update_config, ConfigUpdate, MarketApp,
+};
+
+// Instantiates the market with the specified failure policy and creates an
+// order that fails when accepted.
+fn setup_failing_order(failure_policy: FailurePolicy) -> (MarketApp, Addr, Addr, Addr) {
+    let maker = Addr::unchecked("maker".to_string());
+    let taker = Addr::unchecked("0xstepit".to_string());
+    let (mut app, market_addr) = setup_market(&[
+        (
+            maker.as_str(),
+            &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uatom")],
+        ),
+        (taker.as_str(), &[Coin::new(1_000, "usdc")]),
+    ]);
+    let update = ConfigUpdate {
+        failure_policy: Some(failure_policy),
+        ..Default::default()
+    };
+    update_config(&mut app, &market_addr, update);
+
+    create_order(&mut app, &market_addr, maker.as_str(), order_params()).unwrap();
+    fail_settlements(&mut app, true);
+
+    (app, market_addr, maker, taker)
+}
+
+fn accept_failing_order(
+    app: &mut MarketApp,
+    market_addr: &Addr,
+    maker: &Addr,
+    taker: &Addr,
+) -> AppResponse {
+    accept_order(app, market_addr, taker.as_str(), maker.as_str(), 0).unwrap()
+}
+
+fn query_order(app: &MarketApp, market_addr: &Addr, maker: &Addr) -> SwapOrder {
+    query_orders(app, market_addr, maker.as_str())[0].1.clone()
+}
+
+#[test]
+fn test_failure_policy_refund_and_close() {
+    let (mut app, market_addr, maker, taker) = setup_failing_order(FailurePolicy::RefundAndClose);
+
+    let res = accept_failing_order(&mut app, &market_addr, &maker, &taker);
+    res.assert_event(&Event::new("wasm-swap_order_closed").add_attribute("order_id", "0"));
+
+    let order = query_order(&app, &market_addr, &maker);
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert_eq!(order.taker, Some(taker.clone()));
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(1_000, "usdc"),
+        "expected the taker to be refunded"
+    );
+}
+
+#[test]
+fn test_failure_policy_refund_and_reopen() {
+    let (mut app, market_addr, maker, taker) = setup_failing_order(FailurePolicy::RefundAndReopen);
+
+    let res = accept_failing_order(&mut app, &market_addr, &maker, &taker);
+    res.assert_event(&Event::new("wasm-swap_order_reopened").add_attribute("order_id", "0"));
+
+    let order = query_order(&app, &market_addr, &maker);
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(order.taker, None, "expected the taker to be removed");
+    assert!(order.failure_reason.is_some());
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(1_000, "usdc"),
+        "expected the taker to be refunded"
+    );
+
+    // The order can be accepted again once the settlement works.
+    fail_settlements(&mut app, false);
+    accept_failing_order(&mut app, &market_addr, &maker, &taker);
+
+    let order = query_order(&app, &market_addr, &maker);
+    assert_eq!(order.status, OrderStatus::Accepted);
+}
+
+#[test]
+fn test_failure_policy_refund_and_penalize() {
+    let (mut app, market_addr, maker, taker) =
+        setup_failing_order(FailurePolicy::RefundAndPenalize {
+            penalty: Coin::new(100, "uatom"),
+        });
+
+    app.execute_contract(
+        maker.clone(),
+        market_addr.clone(),
+        &ExecuteMsg::DepositBond { order_id: None },
+        &[Coin::new(150, "uatom")],
+    )
+    .unwrap();
+
+    let res = accept_failing_order(&mut app, &market_addr, &maker, &taker);
+    res.assert_event(&Event::new("wasm-maker_penalized").add_attribute("penalty", "100uatom"));
+
+    let order = query_order(&app, &market_addr, &maker);
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert_eq!(
+        app.wrap().query_balance(&taker, "usdc").unwrap(),
+        Coin::new(1_000, "usdc"),
+        "expected the taker to be refunded"
+    );
+    assert_eq!(
+        app.wrap().query_balance(&taker, "uatom").unwrap(),
+        Coin::new(100, "uatom"),
+        "expected the taker to receive the penalty"
+    );
+
+    // Only the remaining bond is paid when lower than the penalty.
+    let order_id = create_order(&mut app, &market_addr, maker.as_str(), order_params()).unwrap();
+    let res = accept_order(
+        &mut app,
+        &market_addr,
+        taker.as_str(),
+        maker.as_str(),
+        order_id,
+    )
+    .unwrap();
+    res.assert_event(&Event::new("wasm-maker_penalized").add_attribute("penalty", "50uatom"));
+    assert_eq!(
+        app.wrap().query_balance(&taker, "uatom").unwrap(),
+        Coin::new(150, "uatom"),
+        "expected the taker to receive the remaining bond"
+    );
+}
+
+#[test]
+fn test_failure_policy_penalize_order_bond() {
+    let (mut app, market_addr, maker, taker) =
+        setup_failing_order(FailurePolicy::RefundAndPenalize {
+            penalty: Coin::new(100, "uatom"),
+        });
+
+    for (order_id, bond) in [(None, 100), (Some(0), 60)] {
+        app.execute_contract(
+            maker.clone(),
+            market_addr.clone(),
+            &ExecuteMsg::DepositBond { order_id },
+            &[Coin::new(bond, "uatom")],
+        )
+        .unwrap();
+    }
+
+    let res = accept_failing_order(&mut app, &market_addr, &maker, &taker);
+    res.assert_event(&Event::new("wasm-maker_penalized").add_attribute("penalty", "100uatom"));
+    assert_eq!(
+        app.wrap().query_balance(&taker, "uatom").unwrap(),
+        Coin::new(100, "uatom"),
+        "expected the taker to receive the penalty"
+    );
+
+    let resp: MakerBondResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &market_addr,
+            &QueryMsg::MakerBond {
+                maker: maker.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        resp,
+        MakerBondResponse {
+            bond: vec![Coin::new(60, "uatom")],
+            order_bonds: vec![],
+        },
+        "expected the order bond slashed before the maker bond"
+    );
+
+    // The failed order is closed, so the remaining bond can be withdrawn.
+    app.execute_contract(
+        maker.clone(),
+        market_addr,
+        &ExecuteMsg::WithdrawBond { order_id: None },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance(&maker, "uatom").unwrap(),
+        Coin::new(900, "uatom")
+    );
+}
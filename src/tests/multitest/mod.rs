@@ -1,4 +1,224 @@
 mod accept_swap_order;
+mod accept_swap_orders;
+mod compliance_hook;
 mod confirm_swap_order;
 mod create_swap_order;
+mod failure_policy;
+mod order_receipt;
 mod stargate_handler;
+mod taker_group;
+
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{Addr, Coin, Empty};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, BankSudo, Contract, ContractWrapper,
+    DistributionKeeper, Executor, FailingModule, GovFailingModule, IbcFailingModule, StakeKeeper,
+    SudoMsg, WasmKeeper,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ComplianceHookUpdate, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiptContractUpdate,
+    ReservationPolicyUpdate, SuspensionRuleUpdate, SwapOrderParams, SwapOrdersByMakerResponse,
+    TakerGroupUpdate,
+};
+use crate::state::{FailurePolicy, SwapOrder};
+use stargate_handler::CustomStargate;
+
+pub const OWNER: &str = "0xstepit000";
+
+// App using the custom stargate handler.
+pub type MarketApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    CustomStargate,
+>;
+
+// Creates a market contract.
+pub fn atomic_swap_market_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_reply_empty(crate::contract::reply);
+
+    Box::new(contract)
+}
+
+// Instantiates the market owned by `OWNER` and mints the balances.
+pub fn setup_market(balances: &[(&str, &[Coin])]) -> (MarketApp, Addr) {
+    let mut app = AppBuilder::new()
+        .with_stargate(CustomStargate::default())
+        .build(|_, _, _| {});
+
+    let market_id = app.store_code(atomic_swap_market_contract());
+    let market_addr = app
+        .instantiate_contract(
+            market_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg { owner: None },
+            &[],
+            "atomic-swap-market",
+            None,
+        )
+        .unwrap();
+
+    for (address, amount) in balances {
+        app.sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: address.to_string(),
+            amount: amount.to_vec(),
+        }))
+        .unwrap();
+    }
+
+    (app, market_addr)
+}
+
+// Fields of `UpdateConfig`, unchanged by default.
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub failure_policy: Option<FailurePolicy>,
+    pub taker_group: Option<TakerGroupUpdate>,
+    pub compliance_hook: Option<ComplianceHookUpdate>,
+    pub receipt_contract: Option<ReceiptContractUpdate>,
+    pub reservation_policy: Option<ReservationPolicyUpdate>,
+    pub suspension_rule: Option<SuspensionRuleUpdate>,
+}
+
+pub fn update_config(app: &mut MarketApp, market_addr: &Addr, update: ConfigUpdate) {
+    let ConfigUpdate {
+        failure_policy,
+        taker_group,
+        compliance_hook,
+        receipt_contract,
+        reservation_policy,
+        suspension_rule,
+    } = update;
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        market_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            check_maker_balance: None,
+            failure_policy,
+            taker_group,
+            compliance_hook,
+            receipt_contract,
+            reservation_policy,
+            suspension_rule,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+// Executes a message on the market and returns the contract error, if any.
+pub fn execute_market(
+    app: &mut MarketApp,
+    sender: &str,
+    market_addr: &Addr,
+    msg: &ExecuteMsg,
+    funds: &[Coin],
+) -> Result<AppResponse, ContractError> {
+    app.execute_contract(Addr::unchecked(sender), market_addr.clone(), msg, funds)
+        .map_err(|err| err.downcast::<ContractError>().unwrap())
+}
+
+// Parameters of an order selling 1_000uosmo for 1_000usdc for 10 seconds.
+pub fn order_params() -> SwapOrderParams {
+    SwapOrderParams {
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    }
+}
+
+// Creates an order of `maker` and returns its identifier.
+pub fn create_order(
+    app: &mut MarketApp,
+    market_addr: &Addr,
+    maker: &str,
+    params: SwapOrderParams,
+) -> Result<u64, ContractError> {
+    let SwapOrderParams {
+        coin_in,
+        coin_out,
+        allowed_takers,
+        taker_merkle_root,
+        taker_group,
+        mint_receipt,
+        reserved_for,
+        timeout,
+    } = params;
+    let msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in,
+        coin_out,
+        allowed_takers,
+        taker_merkle_root,
+        taker_group,
+        mint_receipt,
+        reserved_for,
+        timeout,
+    };
+    let res = execute_market(app, maker, market_addr, &msg, &[])?;
+    let order_id = res
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "order_id")
+        .unwrap();
+    Ok(order_id.value.parse().unwrap())
+}
+
+// Accepts an order of `maker` sending 1_000usdc.
+pub fn accept_order(
+    app: &mut MarketApp,
+    market_addr: &Addr,
+    taker: &str,
+    maker: &str,
+    order_id: u64,
+) -> Result<AppResponse, ContractError> {
+    let msg = ExecuteMsg::AcceptSwapOrder {
+        order_id,
+        maker: maker.to_string(),
+        proof: vec![],
+    };
+    execute_market(app, taker, market_addr, &msg, &[Coin::new(1_000, "usdc")])
+}
+
+pub fn query_orders(app: &MarketApp, market_addr: &Addr, maker: &str) -> Vec<(u64, SwapOrder)> {
+    let resp: SwapOrdersByMakerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            market_addr,
+            &QueryMsg::SwapOrdersByMaker {
+                maker: maker.to_string(),
+            },
+        )
+        .unwrap();
+    resp.orders
+        .into_iter()
+        .map(|(order_id, resp)| (order_id, resp.order))
+        .collect()
+}
+
+// Makes the custom stargate handler fail the settlements.
+pub fn fail_settlements(app: &mut MarketApp, fail: bool) {
+    let mut current_block = app.block_info();
+    current_block.height = if fail { 1 } else { 2 };
+    app.set_block(current_block);
+}
@@ -0,0 +1,202 @@
+use cosmwasm_std::{Addr, Coin, Empty};
+use cw721::OwnerOfResponse;
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, ReceiptContractUpdate, SwapOrderParams};
+use crate::state::OrderStatus;
+use crate::tests::multitest::{
+    accept_order, create_order, execute_market, order_params, query_orders, setup_market,
+    update_config, ConfigUpdate, MarketApp, OWNER,
+};
+
+fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    Box::new(contract)
+}
+
+// Instantiates the market and a cw721 contract whose minter is the market.
+fn setup_market_and_receipts() -> (MarketApp, Addr, Addr) {
+    let (mut app, market_addr) = setup_market(&[
+        ("maker", &[Coin::new(1_000, "uosmo")]),
+        ("taker", &[Coin::new(1_000, "usdc")]),
+    ]);
+
+    let receipts_id = app.store_code(cw721_base_contract());
+    let receipts_addr = app
+        .instantiate_contract(
+            receipts_id,
+            Addr::unchecked(OWNER),
+            &cw721_base::InstantiateMsg {
+                name: "Swap order receipts".to_string(),
+                symbol: "RECEIPT".to_string(),
+                minter: market_addr.to_string(),
+            },
+            &[],
+            "order-receipts",
+            None,
+        )
+        .unwrap();
+
+    (app, market_addr, receipts_addr)
+}
+
+fn set_receipt_contract(app: &mut MarketApp, market_addr: &Addr, update: ReceiptContractUpdate) {
+    let update = ConfigUpdate {
+        receipt_contract: Some(update),
+        ..Default::default()
+    };
+    update_config(app, market_addr, update);
+}
+
+fn create_receipt_order(
+    app: &mut MarketApp,
+    market_addr: &Addr,
+    mint_receipt: bool,
+) -> Result<u64, ContractError> {
+    let params = SwapOrderParams {
+        mint_receipt,
+        ..order_params()
+    };
+    create_order(app, market_addr, "maker", params)
+}
+
+#[test]
+fn test_order_receipt_proceeds() {
+    let (mut app, market_addr, receipts_addr) = setup_market_and_receipts();
+    set_receipt_contract(
+        &mut app,
+        &market_addr,
+        ReceiptContractUpdate::Set(receipts_addr.to_string()),
+    );
+    create_receipt_order(&mut app, &market_addr, true).unwrap();
+
+    let resp: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            receipts_addr.clone(),
+            &cw721_base::QueryMsg::<Empty>::OwnerOf {
+                token_id: "0".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(resp.owner, "maker", "expected receipt minted to the maker");
+
+    // The maker hands off the order to the buyer.
+    app.execute_contract(
+        Addr::unchecked("maker"),
+        receipts_addr,
+        &cw721_base::ExecuteMsg::<cw721_base::Extension, Empty>::TransferNft {
+            recipient: "buyer".to_string(),
+            token_id: "0".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    accept_order(&mut app, &market_addr, "taker", "maker", 0).unwrap();
+    // The custom stargate handler doesn't execute the `MsgExec`, so the
+    // confirmation is sent by the maker as `x/authz` would do.
+    app.execute_contract(
+        Addr::unchecked("maker"),
+        market_addr,
+        &ExecuteMsg::ConfirmSwapOrder {
+            order_id: 0,
+            maker: "maker".to_string(),
+        },
+        &[Coin::new(1_000, "uosmo")],
+    )
+    .unwrap();
+
+    let resp = app.wrap().query_balance("buyer", "usdc").unwrap();
+    assert_eq!(
+        resp,
+        Coin::new(1_000, "usdc"),
+        "expected proceeds to holder"
+    );
+    let resp = app.wrap().query_balance("maker", "usdc").unwrap();
+    assert_eq!(resp, Coin::new(0, "usdc"));
+    let resp = app.wrap().query_balance("maker", "uosmo").unwrap();
+    assert_eq!(resp, Coin::new(0, "uosmo"), "expected maker funds pulled");
+    let resp = app.wrap().query_balance("taker", "uosmo").unwrap();
+    assert_eq!(resp, Coin::new(1_000, "uosmo"));
+}
+
+#[test]
+fn test_order_receipt_handed_off() {
+    let (mut app, market_addr, receipts_addr) = setup_market_and_receipts();
+    set_receipt_contract(
+        &mut app,
+        &market_addr,
+        ReceiptContractUpdate::Set(receipts_addr.to_string()),
+    );
+    create_receipt_order(&mut app, &market_addr, true).unwrap();
+    app.execute_contract(
+        Addr::unchecked("maker"),
+        receipts_addr,
+        &cw721_base::ExecuteMsg::<cw721_base::Extension, Empty>::TransferNft {
+            recipient: "buyer".to_string(),
+            token_id: "0".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let transferred = ContractError::ReceiptTransferred {
+        holder: "buyer".to_string(),
+    };
+    let amend_msg = ExecuteMsg::AmendSwapOrder {
+        order_id: 0,
+        maker: None,
+        price: Some(1u128.into()),
+        timeout: None,
+    };
+    let err = execute_market(&mut app, "maker", &market_addr, &amend_msg, &[]).unwrap_err();
+    assert_eq!(err, transferred);
+    let cancel_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![0],
+    };
+    let err = execute_market(&mut app, "maker", &market_addr, &cancel_msg, &[]).unwrap_err();
+    assert_eq!(err, transferred);
+
+    create_receipt_order(&mut app, &market_addr, false).unwrap();
+    let cancel_all_msg = ExecuteMsg::CancelAllMyOrders {
+        maker: None,
+        denom_pair: None,
+    };
+    execute_market(&mut app, "maker", &market_addr, &cancel_all_msg, &[]).unwrap();
+    let orders = query_orders(&app, &market_addr, "maker");
+    assert_eq!(
+        orders
+            .iter()
+            .map(|(_, order)| order.status.clone())
+            .collect::<Vec<OrderStatus>>(),
+        vec![OrderStatus::Open, OrderStatus::Deleted],
+        "expected only the handed off order to stay open"
+    );
+}
+
+#[test]
+fn test_order_receipt_not_enabled() {
+    let (mut app, market_addr, receipts_addr) = setup_market_and_receipts();
+
+    let err = create_receipt_order(&mut app, &market_addr, true).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptsNotEnabled {});
+
+    set_receipt_contract(
+        &mut app,
+        &market_addr,
+        ReceiptContractUpdate::Set(receipts_addr.to_string()),
+    );
+    create_receipt_order(&mut app, &market_addr, true).unwrap();
+    set_receipt_contract(&mut app, &market_addr, ReceiptContractUpdate::Remove {});
+    let err = create_receipt_order(&mut app, &market_addr, true).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptsNotEnabled {});
+    create_receipt_order(&mut app, &market_addr, false).unwrap();
+}
@@ -1,11 +1,15 @@
 use anyhow::Result as AnyResult;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_schema::schemars::JsonSchema;
-use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomQuery, Storage};
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, Coin, CustomQuery, Querier, Storage};
 use cw_multi_test::{AppResponse, CosmosRouter, Stargate};
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 
+use crate::tests::mock_querier::{
+    contract_execution_grant, grants_query_grantee, GRANTS_QUERY_PATH,
+};
+
 #[cw_serde]
 #[derive(Default)]
 pub struct CustomStargate {}
@@ -33,4 +37,30 @@ impl Stargate for CustomStargate {
             Err(anyhow::anyhow!("Failed to use auhtz"))
         }
     }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        // Every maker granted the contract to spend a large amount of
+        // the denoms used in tests without expiration.
+        if path == GRANTS_QUERY_PATH {
+            Ok(contract_execution_grant(
+                &grants_query_grantee(&data),
+                &[
+                    Coin::new(1_000_000, "uatom"),
+                    Coin::new(1_000_000, "uosmo"),
+                    Coin::new(1_000_000, "usdc"),
+                ],
+                None,
+            ))
+        } else {
+            Err(anyhow::anyhow!("Unexpected stargate query: {path}"))
+        }
+    }
 }
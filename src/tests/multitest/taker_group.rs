@@ -0,0 +1,139 @@
+use cosmwasm_std::{Addr, Coin, Empty};
+use cw4::Member;
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+
+use crate::error::ContractError;
+use crate::msg::{SwapOrderParams, TakerGroupParams, TakerGroupUpdate};
+use crate::state::OrderStatus;
+use crate::tests::multitest::{
+    accept_order, create_order, order_params, query_orders, setup_market, update_config,
+    ConfigUpdate, MarketApp, OWNER,
+};
+
+fn cw4_group_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw4_group::contract::execute,
+        cw4_group::contract::instantiate,
+        cw4_group::contract::query,
+    );
+    Box::new(contract)
+}
+
+// Instantiates the market and a cw4 group with a light and a heavy member.
+fn setup_market_and_group() -> (MarketApp, Addr, Addr) {
+    let (mut app, market_addr) = setup_market(&[
+        ("maker", &[Coin::new(2_000, "uosmo")]),
+        ("light", &[Coin::new(2_000, "usdc")]),
+        ("heavy", &[Coin::new(2_000, "usdc")]),
+        ("outsider", &[Coin::new(2_000, "usdc")]),
+    ]);
+
+    let group_id = app.store_code(cw4_group_contract());
+    let group_addr = app
+        .instantiate_contract(
+            group_id,
+            Addr::unchecked(OWNER),
+            &cw4_group::msg::InstantiateMsg {
+                admin: None,
+                members: vec![
+                    Member {
+                        addr: "light".to_string(),
+                        weight: 1,
+                    },
+                    Member {
+                        addr: "heavy".to_string(),
+                        weight: 5,
+                    },
+                ],
+            },
+            &[],
+            "cw4-group",
+            None,
+        )
+        .unwrap();
+
+    (app, market_addr, group_addr)
+}
+
+fn create_group_order(
+    app: &mut MarketApp,
+    market_addr: &Addr,
+    taker_group: Option<TakerGroupParams>,
+) -> u64 {
+    let params = SwapOrderParams {
+        taker_group,
+        ..order_params()
+    };
+    create_order(app, market_addr, "maker", params).unwrap()
+}
+
+#[test]
+fn test_order_taker_group() {
+    let (mut app, market_addr, group_addr) = setup_market_and_group();
+    let order_id = create_group_order(
+        &mut app,
+        &market_addr,
+        Some(TakerGroupParams {
+            group: group_addr.to_string(),
+            min_weight: Some(2),
+        }),
+    );
+
+    let err = accept_order(&mut app, &market_addr, "outsider", "maker", order_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotGroupMember {
+            taker: "outsider".to_string(),
+            group: group_addr.to_string(),
+        }
+    );
+
+    let err = accept_order(&mut app, &market_addr, "light", "maker", order_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GroupWeightTooLow {
+            weight: 1,
+            min_weight: 2,
+        }
+    );
+
+    accept_order(&mut app, &market_addr, "heavy", "maker", order_id).unwrap();
+    let orders = query_orders(&app, &market_addr, "maker");
+    assert_eq!(orders[0].1.status, OrderStatus::Accepted);
+    assert_eq!(orders[0].1.taker, Some(Addr::unchecked("heavy")));
+}
+
+#[test]
+fn test_market_taker_group() {
+    let (mut app, market_addr, group_addr) = setup_market_and_group();
+    let update_group = |app: &mut MarketApp, taker_group| {
+        let update = ConfigUpdate {
+            taker_group: Some(taker_group),
+            ..Default::default()
+        };
+        update_config(app, &market_addr, update);
+    };
+    update_group(
+        &mut app,
+        TakerGroupUpdate::Set(TakerGroupParams {
+            group: group_addr.to_string(),
+            min_weight: None,
+        }),
+    );
+
+    let order_id = create_group_order(&mut app, &market_addr, None);
+    let err = accept_order(&mut app, &market_addr, "outsider", "maker", order_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotGroupMember {
+            taker: "outsider".to_string(),
+            group: group_addr.to_string(),
+        },
+        "expected the market group to apply to all the orders"
+    );
+    accept_order(&mut app, &market_addr, "light", "maker", order_id).unwrap();
+
+    update_group(&mut app, TakerGroupUpdate::Remove {});
+    let order_id = create_group_order(&mut app, &market_addr, None);
+    accept_order(&mut app, &market_addr, "outsider", "maker", order_id).unwrap();
+}
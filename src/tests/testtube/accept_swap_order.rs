@@ -5,7 +5,7 @@ use osmosis_std::shim::{Any, Timestamp};
 use osmosis_std::types::cosmos::authz::v1beta1::{
     Grant, GrantAuthorization, MsgGrant, QueryGranteeGrantsRequest,
 };
-use osmosis_std::types::cosmos::bank::v1beta1::QueryBalanceRequest;
+use osmosis_std::types::cosmos::bank::v1beta1::{MsgSend, QueryBalanceRequest};
 use osmosis_std::types::cosmwasm::wasm::v1::{
     AllowAllMessagesFilter, ContractExecutionAuthorization, ContractGrant, MaxFundsLimit,
 };
@@ -17,111 +17,110 @@ use schemars::JsonSchema;
 use test_tube::cosmrs::proto::prost::Message;
 use test_tube::Account;
 
+use crate::error::ContractError;
 use crate::msg::{AllSwapOrdersResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::SwapOrder;
+use crate::state::{OrderKind, OrderStatus, SwapOrder};
 use crate::tests::testtube::authz::Authz;
 use crate::tests::testtube::test_env::{TestEnvBuilder, WEEK};
 
 #[test]
-fn test_accept_swap_order_fails() {
+fn test_create_swap_order_without_grant_fails() {
     let app = OsmosisTestApp::new();
     let t = TestEnvBuilder::new()
         .with_account("owner", vec![Coin::new(2_000, "ubtc")])
         .with_account("maker", vec![Coin::new(2_000, "uatom")])
-        .with_account(
-            "taker",
-            vec![Coin::new(1_000, "uatom"), Coin::new(2_000, "usdc")],
-        )
         .with_instantiate_msg(InstantiateMsg { owner: None })
         .build(&app);
 
     let maker = t.accounts.get("maker").unwrap();
-    let taker = t.accounts.get("taker").unwrap();
-
-    let bank = Bank::new(&app);
-
-    let response = bank
-        .query_balance(&QueryBalanceRequest {
-            address: maker.address().to_string(),
-            denom: "usdc".to_string(),
-        })
-        .unwrap();
-    assert_eq!(
-        response.balance.unwrap(),
-        OsmosisCoin {
-            amount: 0u128.to_string(),
-            denom: "usdc".to_string(),
-        }
-    );
-    let response = bank
-        .query_balance(&QueryBalanceRequest {
-            address: taker.address().to_string(),
-            denom: "uatom".to_string(),
-        })
-        .unwrap();
-    assert_eq!(
-        response.balance.unwrap(),
-        OsmosisCoin {
-            amount: 1_000u128.to_string(),
-            denom: "uatom".to_string(),
-        }
-    );
 
     // ---------------------------------------------------------------------------------------------
-    // Update contract state using the grant
+    // Create an order without granting the contract
     // ---------------------------------------------------------------------------------------------
 
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uatom"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
 
-    t.contract.execute(&create_order_msg, &[], maker).unwrap();
+    let err = t
+        .contract
+        .execute(&create_order_msg, &[], maker)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(&ContractError::MissingAuthzGrant {}.to_string()),
+        "expected error because the maker didn't grant the contract"
+    );
 
     let orders: AllSwapOrdersResponse = t.contract.query(&QueryMsg::AllSwapOrders {}).unwrap();
-    assert_eq!(
-        orders.orders.len(),
-        1,
-        "expect to have the swap order stored"
-    );
+    assert_eq!(orders.orders.len(), 0, "expect no swap order stored");
+}
 
-    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
-        order_id: 0,
-        maker: maker.address().to_string(),
+#[test]
+fn test_create_swap_order_with_insufficient_grant_fails() {
+    let app = OsmosisTestApp::new();
+    let t = TestEnvBuilder::new()
+        .with_account("owner", vec![Coin::new(2_000, "ubtc")])
+        .with_account("maker", vec![Coin::new(2_000, "uatom")])
+        .with_instantiate_msg(InstantiateMsg { owner: None })
+        .build(&app);
+
+    let market_address = t.contract.contract_addr.clone();
+    let maker = t.accounts.get("maker").unwrap();
+
+    let authz = Authz::new(&app);
+
+    // ---------------------------------------------------------------------------------------------
+    // Grant the contract less than the coin to swap
+    // ---------------------------------------------------------------------------------------------
+
+    let expiration = Timestamp {
+        seconds: app.get_block_timestamp().seconds() as i64 + WEEK,
+        nanos: 0_i32,
     };
-    t.contract
-        .execute(&accept_order_msg, &[Coin::new(1_000, "usdc")], taker)
+    authz
+        .grant_contract_execution(
+            maker,
+            &market_address,
+            vec![Coin::new(500, "uatom")],
+            expiration,
+        )
         .unwrap();
 
-    let response = bank
-        .query_balance(&QueryBalanceRequest {
-            address: maker.address().to_string(),
-            denom: "uatom".to_string(),
-        })
-        .unwrap();
-    assert_eq!(
-        response.balance.unwrap(),
-        OsmosisCoin {
-            amount: 2_000u128.to_string(),
-            denom: "uatom".to_string(),
-        },
-        "expect maker to have original funds"
-    );
-    let response = bank
-        .query_balance(&QueryBalanceRequest {
-            address: taker.address().to_string(),
-            denom: "usdc".to_string(),
-        })
-        .unwrap();
-    assert_eq!(
-        response.balance.unwrap(),
-        OsmosisCoin {
-            amount: 2_000u128.to_string(),
-            denom: "usdc".to_string(),
-        },
-        "expect taker to have original funds"
+    let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    };
+
+    let err = t
+        .contract
+        .execute(&create_order_msg, &[], maker)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            &ContractError::GrantLimitExceeded {
+                denom: "uatom".to_string(),
+                limit: 500,
+                required: 1_000,
+            }
+            .to_string()
+        ),
+        "expected error because the grant doesn't cover the coin to swap"
     );
 }
 
@@ -261,9 +260,14 @@ fn test_accept_swap_order() {
     // ---------------------------------------------------------------------------------------------
 
     let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
         coin_in: Coin::new(1_000, "uatom"),
         coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
         timeout: 10,
     };
 
@@ -280,6 +284,7 @@ fn test_accept_swap_order() {
     let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
         order_id: 0,
         maker: maker.address().to_string(),
+        proof: vec![],
     };
     t.contract
         .execute(&accept_order_msg, &[Coin::new(1_000, "usdc")], taker)
@@ -316,14 +321,129 @@ fn test_accept_swap_order() {
 
     let orders: AllSwapOrdersResponse = t.contract.query(&QueryMsg::AllSwapOrders {}).unwrap();
     assert_eq!(
-        orders.orders[0].1,
+        orders.orders[0].1.order,
         SwapOrder {
             coin_in: Coin::new(1_000, "uatom"),
             coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![],
+            taker_merkle_root: None,
+            taker_group: None,
             taker: Some(Addr::unchecked(taker.address())),
             timeout: 10 + block_seconds,
             status: crate::state::OrderStatus::Confirmed,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
         },
         "expect to have one swap order active"
     );
 }
+
+#[test]
+fn test_accept_swap_order_maker_insufficient_funds() {
+    let app = OsmosisTestApp::new();
+    let t = TestEnvBuilder::new()
+        .with_account("owner", vec![Coin::new(2_000, "ubtc")])
+        .with_account("maker", vec![Coin::new(2_000, "uatom")])
+        .with_account("taker", vec![Coin::new(2_000, "usdc")])
+        .with_instantiate_msg(InstantiateMsg { owner: None })
+        .build(&app);
+
+    let market_address = t.contract.contract_addr.clone();
+    let owner = t.accounts.get("owner").unwrap();
+    let maker = t.accounts.get("maker").unwrap();
+    let taker = t.accounts.get("taker").unwrap();
+
+    let authz = Authz::new(&app);
+    let bank = Bank::new(&app);
+
+    // ---------------------------------------------------------------------------------------------
+    // Create an order and move the maker funds away
+    // ---------------------------------------------------------------------------------------------
+
+    let expiration = Timestamp {
+        seconds: app.get_block_timestamp().seconds() as i64 + WEEK,
+        nanos: 0_i32,
+    };
+    authz
+        .grant_contract_execution(
+            maker,
+            &market_address,
+            vec![Coin::new(2_000, "uatom")],
+            expiration,
+        )
+        .unwrap();
+
+    let create_order_msg = ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    };
+    t.contract.execute(&create_order_msg, &[], maker).unwrap();
+
+    bank.send(
+        MsgSend {
+            from_address: maker.address(),
+            to_address: owner.address(),
+            amount: vec![Coin::new(1_500, "uatom").into()],
+        },
+        maker,
+    )
+    .unwrap();
+
+    // ---------------------------------------------------------------------------------------------
+    // Accept the order
+    // ---------------------------------------------------------------------------------------------
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: maker.address().to_string(),
+        proof: vec![],
+    };
+    let err = t
+        .contract
+        .execute(&accept_order_msg, &[Coin::new(1_000, "usdc")], taker)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            &ContractError::MakerInsufficientFunds {
+                denom: "uatom".to_string(),
+                balance: 500,
+                required: 1_000,
+            }
+            .to_string()
+        ),
+        "expected error because the maker cannot settle the order"
+    );
+
+    let response = bank
+        .query_balance(&QueryBalanceRequest {
+            address: taker.address().to_string(),
+            denom: "usdc".to_string(),
+        })
+        .unwrap();
+    assert_eq!(
+        response.balance.unwrap(),
+        OsmosisCoin {
+            amount: 2_000u128.to_string(),
+            denom: "usdc".to_string(),
+        },
+        "expect taker to have original funds"
+    );
+
+    let orders: AllSwapOrdersResponse = t.contract.query(&QueryMsg::AllSwapOrders {}).unwrap();
+    assert_eq!(
+        orders.orders[0].1.order.status,
+        OrderStatus::Open,
+        "expect the order to be still open"
+    );
+}
@@ -1,12 +1,18 @@
 #![cfg(not(tarpaulin_include))]
+use cosmwasm_std::Coin;
+use osmosis_std::shim::{Any, Timestamp};
 use osmosis_std::types::cosmos::authz::v1beta1::{
-    MsgExec, MsgExecResponse, MsgGrant, MsgGrantResponse, QueryGranteeGrantsRequest,
+    Grant, MsgExec, MsgExecResponse, MsgGrant, MsgGrantResponse, QueryGranteeGrantsRequest,
     QueryGranteeGrantsResponse,
 };
+use osmosis_std::types::cosmwasm::wasm::v1::{
+    AllowAllMessagesFilter, ContractExecutionAuthorization, ContractGrant, MaxFundsLimit,
+};
 use test_tube::{fn_execute, fn_query};
 
 use test_tube::module::Module;
 use test_tube::runner::Runner;
+use test_tube::{Account, RunnerExecuteResult, SigningAccount};
 
 pub struct Authz<'a, R: Runner<'a>> {
     runner: &'a R,
@@ -34,3 +40,46 @@ where
         pub query_grantee_grants ["/cosmos.authz.v1beta1.Query/GranteeGrants"]: QueryGranteeGrantsRequest => QueryGranteeGrantsResponse
     }
 }
+
+impl<'a, R> Authz<'a, R>
+where
+    R: Runner<'a>,
+{
+    /// Grants `contract` a `ContractExecutionAuthorization` to spend up to
+    /// `limit` on behalf of `granter` until `expiration`.
+    pub fn grant_contract_execution(
+        &self,
+        granter: &SigningAccount,
+        contract: &str,
+        limit: Vec<Coin>,
+        expiration: Timestamp,
+    ) -> RunnerExecuteResult<MsgGrantResponse> {
+        let authorization = ContractExecutionAuthorization {
+            grants: vec![ContractGrant {
+                contract: contract.to_string(),
+                limit: Some(
+                    MaxFundsLimit {
+                        amounts: limit.into_iter().map(Into::into).collect(),
+                    }
+                    .to_any(),
+                ),
+                filter: Some(AllowAllMessagesFilter {}.to_any()),
+            }],
+        };
+
+        self.grant(
+            MsgGrant {
+                granter: granter.address(),
+                grantee: contract.to_string(),
+                grant: Some(Grant {
+                    authorization: Some(Any {
+                        type_url: ContractExecutionAuthorization::TYPE_URL.to_string(),
+                        value: authorization.to_proto_bytes(),
+                    }),
+                    expiration: Some(expiration),
+                }),
+            },
+            granter,
+        )
+    }
+}
@@ -1,21 +1,43 @@
 use cosmwasm_std::{
     from_json,
-    testing::{mock_dependencies, mock_env, mock_info},
-    Addr, Coin, Reply, SubMsgResponse, SubMsgResult, Uint128,
+    testing::{
+        mock_dependencies, mock_dependencies_with_balances, mock_env, mock_info, MockApi,
+        MockStorage,
+    },
+    Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, HexBinary, OwnedDeps, Reply,
+    ReplyOn, StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
 };
-use osmosis_std::types::cosmos::authz::v1beta1::MsgExecResponse;
+use osmosis_std::types::cosmos::authz::v1beta1::{MsgExec, MsgExecResponse};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    contract::{execute, instantiate, query, reply, CONFIRM_ORDER_REPLY_ID},
+    contract::{execute, instantiate, query, reply},
     error::ContractError,
-    msg::{InstantiateMsg, QueryMsg, SwapOrdersByMakerResponse},
+    msg::{
+        AcceptMode, AcceptSwapOrdersResponse, BlockedTakersResponse, CounterOffersResponse,
+        CurrentPriceResponse, HtlcOrdersByMakerResponse, InstantiateMsg, MakerBondResponse,
+        MakerExposureResponse, MakerStatsResponse, OperatorsResponse, QueryMsg,
+        QuoteRequestsResponse, QuotesByRequestResponse, ReservationPolicyUpdate,
+        SuspensionRuleUpdate, SwapOrderParams, SwapOrdersByMakerResponse,
+    },
     state::Config,
 };
 use crate::{
     msg::ExecuteMsg,
-    state::{OrderPointer, OrderStatus, SwapOrder, CONFIG, ORDER_POINTER, SWAP_ORDERS},
+    state::{
+        CounterOffer, FailurePolicy, HtlcOrder, MakerStats, OperatorPermission, OrderKind,
+        OrderPhase, OrderPointer, OrderStatus, QuoteRequest, Reservation, ReservationPolicy,
+        SuspensionRule, SwapOrder, ACCEPTED_OFFERS, CONFIG, COUNTER_OFFERS, HTLC_ORDERS,
+        MAKER_BONDS, MATCHED_ORDERS, PENDING_MATCHES, PENDING_SETTLEMENTS, QUOTE_REQUESTS,
+        SWAP_ORDERS,
+    },
 };
 
+use crate::merkle::{self, MerkleTree};
+use crate::tests::helpers::{create_order, create_order_msg, mock_market};
+use crate::tests::mock_querier::{
+    contract_execution_grant, mock_dependencies_with_grants, AuthzMockQuerier,
+};
 use crate::utils;
 
 #[test]
@@ -37,6 +59,13 @@ fn test_instatiate() {
     let config = CONFIG.load(deps.as_ref().storage).unwrap();
     let expected_config = Config {
         owner: Addr::unchecked("pit"),
+        check_maker_balance: false,
+        failure_policy: FailurePolicy::RefundAndClose,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
     };
     assert_eq!(
         expected_config, config,
@@ -49,6 +78,13 @@ fn test_instatiate() {
     let config = CONFIG.load(deps.as_ref().storage).unwrap();
     let expected_config = Config {
         owner: Addr::unchecked("ste"),
+        check_maker_balance: false,
+        failure_policy: FailurePolicy::RefundAndClose,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
     };
     assert_eq!(expected_config, config, "expected info sender as owner");
 }
@@ -76,8 +112,12 @@ fn test_instatiate_handling_errors() {
 
 #[test]
 fn test_creare_swap_order() {
-    let mut deps = mock_dependencies();
     let env = mock_env();
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(1_000, "uatom")],
+        None,
+    )));
     let info = mock_info("maker", &[]);
 
     instantiate(
@@ -90,12 +130,8 @@ fn test_creare_swap_order() {
     )
     .unwrap();
 
-    let create_order_msg = ExecuteMsg::CreateSwapOrder {
-        coin_in: Coin::new(1_000, "uatom"),
-        coin_out: Coin::new(1_000, "usdc"),
-        taker: None,
-        timeout: 10,
-    };
+    let create_order_msg =
+        create_order_msg(Coin::new(1_000, "uatom"), Coin::new(1_000, "usdc"), 10);
     execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
 
     let res = query(
@@ -110,13 +146,22 @@ fn test_creare_swap_order() {
 
     assert_eq!(orders.len(), 1, "expected one swap order in the store");
     assert_eq!(
-        orders[0].1,
+        orders[0].1.order,
         SwapOrder {
             coin_in: Coin::new(1_000, "uatom"),
             coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![],
+            taker_merkle_root: None,
+            taker_group: None,
             taker: None,
             timeout: 10 + env.block.time.seconds(),
             status: OrderStatus::Open,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
         },
         "expected a swap order with different values"
     );
@@ -138,13 +183,12 @@ fn test_creare_swap_order_handling_errors() {
     )
     .unwrap();
 
-    let create_order_msg = ExecuteMsg::CreateSwapOrder {
-        coin_in: Coin::new(1_000, "uatom"),
-        coin_out: Coin::new(1_000, "uatom"),
-        taker: None,
-        timeout: 10,
-    };
-    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg(Coin::new(1_000, "uatom"), Coin::new(1_000, "uatom"), 10),
+    );
 
     assert_eq!(
         err.unwrap_err(),
@@ -154,13 +198,12 @@ fn test_creare_swap_order_handling_errors() {
     );
 
     let info = mock_info("maker", &[Coin::new(1_000, "uosmo")]);
-    let create_order_msg = ExecuteMsg::CreateSwapOrder {
-        coin_in: Coin::new(1_000, "uatom"),
-        coin_out: Coin::new(1_000, "uosmo"),
-        taker: None,
-        timeout: 10,
-    };
-    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg(Coin::new(1_000, "uatom"), Coin::new(1_000, "uosmo"), 10),
+    );
 
     assert_eq!(
         err.unwrap_err(),
@@ -172,200 +215,4360 @@ fn test_creare_swap_order_handling_errors() {
 }
 
 #[test]
-fn test_accept_swap_order() {
-    let mut deps = mock_dependencies();
+fn test_creare_swap_order_authz_errors() {
     let env = mock_env();
-    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
-    let taker_addr = Addr::unchecked("taker");
-    let maker_addr = Addr::unchecked("maker");
+    let info = mock_info("maker", &[]);
+    let create_order_msg =
+        create_order_msg(Coin::new(1_000, "uatom"), Coin::new(1_000, "usdc"), 10);
 
-    instantiate(
+    let mut deps = mock_dependencies_with_grants(None);
+    let err = execute(
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        InstantiateMsg {
-            owner: Some("stepit".to_string()),
+        create_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::MissingAuthzGrant {},
+        "expected error when maker didn't grant the contract"
+    );
+
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        "another_contract",
+        &[Coin::new(1_000, "uatom")],
+        None,
+    )));
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::MissingAuthzGrant {},
+        "expected error when the grant is for another contract"
+    );
+
+    // Mock block time is 2019-10-23T02:23:39Z.
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(1_000, "uatom")],
+        Some("2019-10-23T02:23:40Z"),
+    )));
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::GrantExpiresBeforeTimeout {
+            expiration: env.block.time.seconds() + 1,
+            timeout: env.block.time.seconds() + 10,
+        },
+        "expected error when the grant expires before the order"
+    );
+
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(999, "uatom"), Coin::new(1_000, "usdc")],
+        Some("2019-10-23T02:23:49Z"),
+    )));
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::GrantLimitExceeded {
+            denom: "uatom".to_string(),
+            limit: 999,
+            required: 1_000,
         },
+        "expected error when the grant doesn't cover coin in"
+    );
+}
+
+#[test]
+fn test_maker_exposure() {
+    let mut env = mock_env();
+    let info = mock_info("maker", &[]);
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(1_500, "uatom")],
+        None,
+    )));
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
     )
     .unwrap();
 
-    SWAP_ORDERS
-        .save(
-            &mut deps.storage,
-            (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: 10 + env.block.time.seconds(),
-                status: OrderStatus::Open,
+    let create_order_msg = |amount: u128| ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(amount, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    };
+    let maker_exposure = |deps: Deps, env: &Env| {
+        let res = query(
+            deps,
+            env.clone(),
+            QueryMsg::MakerExposure {
+                maker: "maker".to_string(),
             },
         )
         .unwrap();
-
-    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
-        order_id: 0,
-        maker: "maker".to_string(),
+        from_json::<MakerExposureResponse>(res).unwrap().exposure
     };
-    execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
 
-    let res = query(
-        deps.as_ref(),
+    execute(
+        deps.as_mut(),
         env.clone(),
-        QueryMsg::SwapOrdersByMaker {
-            maker: "maker".to_string(),
-        },
+        info.clone(),
+        create_order_msg(1_000),
     )
     .unwrap();
-    let SwapOrdersByMakerResponse { orders } = from_json(res).unwrap();
+    assert_eq!(
+        maker_exposure(deps.as_ref(), &env),
+        vec![Coin::new(1_000, "uatom")],
+        "expected open order counted in the maker exposure"
+    );
 
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg(1_000),
+    );
     assert_eq!(
-        orders[0].1,
-        SwapOrder {
-            coin_in: Coin::new(1_000, "uatom"),
-            coin_out: Coin::new(1_000, "usdc"),
-            taker: Some(taker_addr.clone()),
-            timeout: 10 + env.block.time.seconds(),
-            status: OrderStatus::Accepted,
+        err.unwrap_err(),
+        ContractError::GrantLimitExceeded {
+            denom: "uatom".to_string(),
+            limit: 1_500,
+            required: 2_000,
         },
-        "expect no errors when taker is None"
+        "expected error when open orders exceed the grant"
     );
 
-    SWAP_ORDERS
-        .save(
-            &mut deps.storage,
-            (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: Some(taker_addr.clone()),
-                timeout: 10 + env.block.time.seconds(),
-                status: OrderStatus::Open,
-            },
-        )
-        .unwrap();
-
-    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
-        order_id: 0,
-        maker: "maker".to_string(),
-    };
-    execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
-
-    let res = query(
-        deps.as_ref(),
+    execute(
+        deps.as_mut(),
         env.clone(),
-        QueryMsg::SwapOrdersByMaker {
-            maker: "maker".to_string(),
-        },
+        info.clone(),
+        create_order_msg(500),
     )
     .unwrap();
-    let SwapOrdersByMakerResponse { orders } = from_json(res).unwrap();
+    assert_eq!(
+        maker_exposure(deps.as_ref(), &env),
+        vec![Coin::new(1_500, "uatom")],
+        "expected both open orders counted in the maker exposure"
+    );
 
+    // Expired orders no longer count in the exposure.
+    env.block.time = env.block.time.plus_seconds(11);
     assert_eq!(
-        orders[0].1,
-        SwapOrder {
-            coin_in: Coin::new(1_000, "uatom"),
-            coin_out: Coin::new(1_000, "usdc"),
-            taker: Some(Addr::unchecked("taker".to_string())),
-            timeout: 10 + env.block.time.seconds(),
-            status: OrderStatus::Accepted,
-        },
-        "expect no errors when sender is equal to specified taker"
+        maker_exposure(deps.as_ref(), &env),
+        vec![],
+        "expected expired orders excluded from the maker exposure"
     );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg(1_000),
+    )
+    .unwrap();
 
-    let order_pointer = ORDER_POINTER.load(&deps.storage).unwrap();
+    // Confirmed orders no longer count in the exposure.
+    deps.querier
+        .base
+        .update_balance("maker", vec![Coin::new(1_000, "uatom")]);
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 2,
+        maker: "maker".to_string(),
+        proof: vec![],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 2,
+        maker: "maker".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("maker", &[Coin::new(1_000, "uatom")]),
+        confirm_order_msg,
+    )
+    .unwrap();
     assert_eq!(
-        order_pointer,
-        OrderPointer {
-            maker: maker_addr,
-            taker: taker_addr,
-            order_id: 0,
-        }
+        maker_exposure(deps.as_ref(), &env),
+        vec![],
+        "expected confirmed order excluded from the maker exposure"
     );
 }
 
 #[test]
-fn test_accept_swap_order_handling_errors() {
-    let mut deps = mock_dependencies();
+fn test_maker_exposure_with_balance_check() {
     let env = mock_env();
-    let info = mock_info("taker", &[]);
-    let maker_addr = Addr::unchecked("maker");
-
+    let info = mock_info("maker", &[]);
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(10_000, "uatom")],
+        None,
+    )));
+    deps.querier
+        .base
+        .update_balance("maker", vec![Coin::new(1_500, "uatom")]);
     instantiate(
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        InstantiateMsg {
-            owner: Some("stepit".to_string()),
-        },
+        InstantiateMsg { owner: None },
     )
     .unwrap();
 
-    SWAP_ORDERS
-        .save(
-            &mut deps.storage,
-            (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: 10 + env.block.time.seconds(),
-                status: OrderStatus::Open,
-            },
-        )
-        .unwrap();
-
-    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
-        order_id: 0,
-        maker: "maker".to_string(),
-    };
-    let err = execute(
+    let create_order_msg =
+        create_order_msg(Coin::new(1_000, "uatom"), Coin::new(1_000, "usdc"), 10);
+    execute(
         deps.as_mut(),
         env.clone(),
         info.clone(),
         create_order_msg.clone(),
-    );
-
-    assert_eq!(
-        err.unwrap_err(),
-        ContractError::FundsError {
-            accepted: 1,
-            received: 0
-        },
-        "expected error when accepting order without sending funds"
-    );
-
-    let info = mock_info("maker", &[Coin::new(1_000, "usdc")]);
-    let err = execute(
+    )
+    .unwrap();
+    execute(
         deps.as_mut(),
         env.clone(),
         info.clone(),
         create_order_msg.clone(),
-    );
+    )
+    .unwrap();
 
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        new_owner: None,
+        check_maker_balance: Some(true),
+        failure_policy: None,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[]),
+        update_config_msg.clone(),
+    );
     assert_eq!(
         err.unwrap_err(),
-        ContractError::SenderIsMaker {},
-        "expected error when maker wants to accept own order"
+        ContractError::Unauthorized,
+        "expected error when sender is not the owner"
     );
+    execute(deps.as_mut(), env.clone(), info.clone(), update_config_msg).unwrap();
 
-    let info = mock_info("taker", &[Coin::new(1_000, "uosmo")]);
-    let err = execute(
-        deps.as_mut(),
-        env.clone(),
-        info.clone(),
-        create_order_msg.clone(),
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked("maker"),
+            check_maker_balance: true,
+            failure_policy: FailurePolicy::RefundAndClose,
+            taker_group: None,
+            compliance_hook: None,
+            receipt_contract: None,
+            reservation_policy: None,
+            suspension_rule: None,
+        },
+        "expected config with maker balance check"
     );
 
+    let err = execute(deps.as_mut(), env, info, create_order_msg);
     assert_eq!(
         err.unwrap_err(),
-        ContractError::WrongCoin {
-            sent_denom: "uosmo".to_string(),
-            sent_amount: 1_000,
-            expected_denom: "usdc".to_string(),
-            expected_amount: 1_000,
+        ContractError::MakerInsufficientFunds {
+            denom: "uatom".to_string(),
+            balance: 1_500,
+            required: 3_000,
         },
-        "expected error when sending coin different than requested"
+        "expected error when open orders exceed the maker balance"
+    );
+}
+
+#[test]
+fn test_update_failure_policy_and_deposit_bond() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("owner", &[]);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let update_config_msg = |failure_policy| ExecuteMsg::UpdateConfig {
+        new_owner: None,
+        check_maker_balance: None,
+        failure_policy: Some(failure_policy),
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        update_config_msg(FailurePolicy::RefundAndPenalize {
+            penalty: Coin::new(0, "uatom"),
+        }),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::ZeroPenalty {},
+        "expected error when the penalty is zero"
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        update_config_msg(FailurePolicy::RefundAndPenalize {
+            penalty: Coin::new(100, "uatom"),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        CONFIG.load(deps.as_ref().storage).unwrap().failure_policy,
+        FailurePolicy::RefundAndPenalize {
+            penalty: Coin::new(100, "uatom"),
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("maker", &[]),
+        ExecuteMsg::DepositBond { order_id: None },
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 1,
+            received: 0
+        }
+    );
+
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("maker", &[Coin::new(100, "uatom")]),
+            ExecuteMsg::DepositBond { order_id: None },
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        MAKER_BONDS
+            .load(deps.as_ref().storage, (&Addr::unchecked("maker"), "uatom"))
+            .unwrap(),
+        Uint128::new(200),
+        "expected deposits to increase the maker bond"
+    );
+}
+
+#[test]
+fn test_withdraw_bond() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    for _ in 0..2 {
+        create_order(
+            deps.as_mut(),
+            &env,
+            "alice",
+            Coin::new(50, "uatom"),
+            Coin::new(500, "usdc"),
+            100,
+        );
+    }
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        ExecuteMsg::DepositBond { order_id: None },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(20, "usdc")]),
+        ExecuteMsg::DepositBond { order_id: Some(0) },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(20, "uatom")]),
+        ExecuteMsg::DepositBond { order_id: Some(0) },
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::BondDenomMismatch {
+            denom: "usdc".to_string()
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::MakerBond {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    let resp: MakerBondResponse = from_json(res).unwrap();
+    assert_eq!(
+        resp,
+        MakerBondResponse {
+            bond: vec![Coin::new(100, "uatom")],
+            order_bonds: vec![(0, Coin::new(20, "usdc"))],
+        }
+    );
+
+    let withdraw_bond_msg = |order_id| ExecuteMsg::WithdrawBond { order_id };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(Some(0)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::OrderNotClosed {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(None),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::MakerHasOpenOrders {});
+
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![0],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(Some(0)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(20, "usdc")],
+        })
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(Some(0)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::NoBond {});
+
+    // The maker bond can be withdrawn once the last order expired.
+    env.block.time = env.block.time.plus_seconds(101);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(None),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(100, "uatom")],
+        })
+    );
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &[]),
+        withdraw_bond_msg(None),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::NoBond {});
+}
+
+#[test]
+fn test_maker_stats() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    let update_config_msg = |max_failure_rate| ExecuteMsg::UpdateConfig {
+        new_owner: None,
+        check_maker_balance: None,
+        failure_policy: None,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: Some(SuspensionRuleUpdate::Set(SuspensionRule {
+            max_failure_rate,
+            min_settlements: 2,
+            window: 100,
+        })),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_config_msg(Decimal::percent(150)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::InvalidSuspensionRule {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_config_msg(Decimal::percent(50)),
+    )
+    .unwrap();
+
+    let create_order = |deps: DepsMut, env: Env| {
+        let create_order_msg =
+            create_order_msg(Coin::new(50, "uatom"), Coin::new(500, "usdc"), 1_000);
+        execute(deps, env, mock_info("alice", &[]), create_order_msg)
+    };
+    let fail_settlement = |deps: &mut OwnedDeps<_, _, _, _>, env: Env, order_id| {
+        let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+            order_id,
+            maker: "alice".to_string(),
+            proof: vec![],
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[Coin::new(500, "usdc")]),
+            accept_order_msg,
+        )
+        .unwrap();
+        let reply_msg = Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("failed to execute message".to_string()),
+        };
+        reply(deps.as_mut(), env, reply_msg).unwrap();
+    };
+
+    for _ in 0..4 {
+        create_order(deps.as_mut(), env.clone()).unwrap();
+    }
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![3],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    )
+    .unwrap();
+
+    // Half of the settlements failing doesn't exceed the rule.
+    fail_settlement(&mut deps, env.clone(), 0);
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 1,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 1,
+        maker: "alice".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(50, "uatom")]),
+        confirm_order_msg,
+    )
+    .unwrap();
+    create_order(deps.as_mut(), env.clone()).unwrap();
+
+    fail_settlement(&mut deps, env.clone(), 2);
+    let err = create_order(deps.as_mut(), env.clone());
+    assert_eq!(err.unwrap_err(), ContractError::MakerSuspended {});
+
+    let query_stats = |deps: Deps, env: Env| -> MakerStatsResponse {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::MakerStats {
+                maker: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        from_json(res).unwrap()
+    };
+    assert_eq!(
+        query_stats(deps.as_ref(), env.clone()),
+        MakerStatsResponse {
+            stats: MakerStats {
+                created: 5,
+                confirmed: 1,
+                failed: 2,
+                cancelled: 1,
+                window_start: env.block.time.seconds(),
+                window_confirmed: 1,
+                window_failed: 2,
+            },
+            suspended: true,
+        }
+    );
+
+    // The suspension ends with the window.
+    env.block.time = env.block.time.plus_seconds(100);
+    assert!(!query_stats(deps.as_ref(), env.clone()).suspended);
+    create_order(deps.as_mut(), env).unwrap();
+}
+
+#[test]
+fn test_accept_swap_order() {
+    let mut deps = mock_dependencies_with_balances(&[("maker", &[Coin::new(1_000, "uatom")])]);
+    let env = mock_env();
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            owner: Some("stepit".to_string()),
+        },
+    )
+    .unwrap();
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: 10 + env.block.time.seconds(),
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+        proof: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SwapOrdersByMaker {
+            maker: "maker".to_string(),
+        },
+    )
+    .unwrap();
+    let SwapOrdersByMakerResponse { orders } = from_json(res).unwrap();
+
+    assert_eq!(
+        orders[0].1.order,
+        SwapOrder {
+            coin_in: Coin::new(1_000, "uatom"),
+            coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![],
+            taker_merkle_root: None,
+            taker_group: None,
+            taker: Some(taker_addr.clone()),
+            timeout: 10 + env.block.time.seconds(),
+            status: OrderStatus::Accepted,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
+        },
+        "expect no errors when taker is None"
+    );
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![taker_addr.clone()],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: 10 + env.block.time.seconds(),
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+        proof: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SwapOrdersByMaker {
+            maker: "maker".to_string(),
+        },
+    )
+    .unwrap();
+    let SwapOrdersByMakerResponse { orders } = from_json(res).unwrap();
+
+    assert_eq!(
+        orders[0].1.order,
+        SwapOrder {
+            coin_in: Coin::new(1_000, "uatom"),
+            coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![taker_addr.clone()],
+            taker_merkle_root: None,
+            taker_group: None,
+            taker: Some(Addr::unchecked("taker".to_string())),
+            timeout: 10 + env.block.time.seconds(),
+            status: OrderStatus::Accepted,
+            failure_reason: None,
+            kind: OrderKind::Fixed,
+            receipt: None,
+            reservation: None,
+            reserved_until: None,
+            quote_request: None,
+        },
+        "expect no errors when sender is equal to specified taker"
+    );
+
+    // The first settlement of the test used the reply identifier 0.
+    let order_pointers = PENDING_SETTLEMENTS.load(&deps.storage, 1).unwrap();
+    assert_eq!(
+        order_pointers,
+        vec![OrderPointer {
+            maker: maker_addr,
+            taker: taker_addr.clone(),
+            order_id: 0,
+        }]
+    );
+}
+
+#[test]
+fn test_accept_swap_order_handling_errors() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("taker", &[]);
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            owner: Some("stepit".to_string()),
+        },
+    )
+    .unwrap();
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: 10 + env.block.time.seconds(),
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let create_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 1,
+            received: 0
+        },
+        "expected error when accepting order without sending funds"
+    );
+
+    let info = mock_info("maker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SenderIsMaker {},
+        "expected error when maker wants to accept own order"
+    );
+
+    let info = mock_info("taker", &[Coin::new(1_000, "uosmo")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "uosmo".to_string(),
+            sent_amount: 1_000,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 1_000,
+        },
+        "expected error when sending coin different than requested"
+    );
+
+    let mut expiration_time = env.block.time.seconds() - 1;
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Open.to_string(),
+            expiration: expiration_time
+        },
+        "expected error when order is expired"
+    );
+
+    expiration_time = env.block.time.seconds() + 1;
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Accepted,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Accepted.to_string(),
+            expiration: expiration_time
+        },
+        "expected error when order is not open"
+    );
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![maker_addr.clone()],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        create_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when sender is not the specified taker"
+    );
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+    deps.querier
+        .update_balance("maker", vec![Coin::new(999, "uatom")]);
+
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::MakerInsufficientFunds {
+            denom: "uatom".to_string(),
+            balance: 999,
+            required: 1_000,
+        },
+        "expected error when maker cannot settle the order"
+    );
+}
+
+fn mock_dependencies_for_batch() -> OwnedDeps<MockStorage, MockApi, AuthzMockQuerier> {
+    let mut deps = mock_market(
+        &[Coin::new(10_000, "uatom")],
+        &[
+            ("alice", &[Coin::new(2_000, "uatom")]),
+            ("bob", &[Coin::new(1_000, "uatom")]),
+        ],
+    );
+
+    // Alice creates the orders 0 and 1, bob creates the order 2.
+    for maker in ["alice", "alice", "bob"] {
+        create_order(
+            deps.as_mut(),
+            &mock_env(),
+            maker,
+            Coin::new(1_000, "uatom"),
+            Coin::new(1_000, "usdc"),
+            10,
+        );
+    }
+    deps
+}
+
+#[test]
+fn test_accept_swap_orders() {
+    let mut deps = mock_dependencies_for_batch();
+    let env = mock_env();
+
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![
+            ("alice".to_string(), 0),
+            ("bob".to_string(), 1),
+            ("bob".to_string(), 2),
+            ("alice".to_string(), 1),
+        ],
+        mode: AcceptMode::BestEffort,
+        proofs: vec![],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[Coin::new(3_500, "usdc")]),
+        accept_orders_msg,
+    )
+    .unwrap();
+
+    let AcceptSwapOrdersResponse { results } = from_json(res.data.unwrap()).unwrap();
+    let errors: Vec<bool> = results
+        .iter()
+        .map(|result| result.error.is_some())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![false, true, false, false],
+        "expected only the order not existing for bob to fail"
+    );
+
+    // One settlement for each maker, plus the refund of unused funds.
+    assert_eq!(res.messages.len(), 3);
+    let CosmosMsg::Stargate { value, .. } = &res.messages[0].msg else {
+        panic!("expected a MsgExec");
+    };
+    let msg_exec = <MsgExec as prost::Message>::decode(value.as_slice()).unwrap();
+    assert_eq!(
+        msg_exec.msgs.len(),
+        2,
+        "expected alice orders in one MsgExec"
+    );
+    assert_eq!(
+        res.messages[2].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "taker".to_string(),
+            amount: vec![Coin::new(500, "usdc")],
+        }),
+        "expected unused funds to be refunded"
+    );
+
+    let order_pointers = PENDING_SETTLEMENTS
+        .load(deps.as_ref().storage, res.messages[0].id)
+        .unwrap();
+    assert_eq!(
+        order_pointers
+            .iter()
+            .map(|pointer| pointer.order_id)
+            .collect::<Vec<u64>>(),
+        vec![0, 1]
+    );
+    for (maker, order_id) in [("alice", 0), ("alice", 1), ("bob", 2)] {
+        let order = SWAP_ORDERS
+            .load(deps.as_ref().storage, (&Addr::unchecked(maker), order_id))
+            .unwrap();
+        assert_eq!(order.status, OrderStatus::Accepted);
+    }
+
+    // A failed settlement refunds the taker of all the maker orders.
+    let reply_msg = Reply {
+        id: res.messages[0].id,
+        result: SubMsgResult::Err("failed to execute message".to_string()),
+    };
+    let res = reply(deps.as_mut(), env, reply_msg).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.events.len(), 2);
+}
+
+#[test]
+fn test_accept_swap_orders_handling_errors() {
+    let mut deps = mock_dependencies_for_batch();
+    let env = mock_env();
+    let info = mock_info("taker", &[Coin::new(3_000, "usdc")]);
+
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), accept_orders_msg);
+    assert_eq!(err.unwrap_err(), ContractError::EmptyBatch {});
+
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![("alice".to_string(), 0), ("bob".to_string(), 2)],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[Coin::new(1_500, "usdc")]),
+        accept_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InsufficientBatchFunds {
+            denom: "usdc".to_string(),
+            available: 500,
+            required: 1_000,
+        },
+        "expected error when funds don't cover all the orders"
+    );
+
+    // Errors don't revert the state in unit tests, so a fresh state is used
+    // after a partially accepted batch.
+    let mut deps = mock_dependencies_for_batch();
+
+    // Alice can settle only one of its orders.
+    deps.querier
+        .base
+        .update_balance("alice", vec![Coin::new(1_500, "uatom")]);
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![("alice".to_string(), 0), ("alice".to_string(), 1)],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), accept_orders_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::MakerInsufficientFunds {
+            denom: "uatom".to_string(),
+            balance: 1_500,
+            required: 2_000,
+        },
+        "expected error when the maker cannot settle all the orders"
+    );
+
+    let mut deps = mock_dependencies_for_batch();
+    let accept_orders_msg = ExecuteMsg::AcceptSwapOrders {
+        orders: vec![("alice".to_string(), 0), ("alice".to_string(), 0)],
+        mode: AcceptMode::AllOrNothing,
+        proofs: vec![],
+    };
+    let err = execute(deps.as_mut(), env, info, accept_orders_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Accepted.to_string(),
+            expiration: mock_env().block.time.seconds() + 10,
+        },
+        "expected error when the same order is accepted twice"
+    );
+}
+
+#[test]
+fn test_create_swap_orders() {
+    let mut deps = mock_dependencies_for_batch();
+    let env = mock_env();
+    let order_params = |amount: u128, denom_out: &str| SwapOrderParams {
+        coin_in: Coin::new(amount, "uatom"),
+        coin_out: Coin::new(1_000, denom_out),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 10,
+    };
+
+    let create_orders_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![order_params(1_000, "usdc"), order_params(2_000, "uosmo")],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_orders_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "3,4");
+
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 4))
+        .unwrap();
+    assert_eq!(order.coin_out, Coin::new(1_000, "uosmo"));
+    assert_eq!(order.status, OrderStatus::Open);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::MakerExposure {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_json::<MakerExposureResponse>(res).unwrap().exposure,
+        vec![Coin::new(5_000, "uatom")],
+        "expected all the orders of the batch counted in the maker exposure"
+    );
+
+    // The orders of the batch are checked together against the grant.
+    let create_orders_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![order_params(3_000, "usdc"), order_params(3_000, "usdc")],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::GrantLimitExceeded {
+            denom: "uatom".to_string(),
+            limit: 10_000,
+            required: 11_000,
+        }
+    );
+
+    let create_orders_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_orders_msg,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::EmptyBatch {});
+
+    let create_orders_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![order_params(1_000, "uatom")],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &[]),
+        create_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SameDenomError {
+            denom: "uatom".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_cancel_swap_orders() {
+    let mut deps = mock_dependencies_for_batch();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![0, 1],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg.clone(),
+    )
+    .unwrap();
+    for order_id in [0, 1] {
+        let order = SWAP_ORDERS.load(&deps.storage, (&alice, order_id)).unwrap();
+        assert_eq!(order.status, OrderStatus::Deleted);
+    }
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::MakerExposure {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(
+        from_json::<MakerExposureResponse>(res)
+            .unwrap()
+            .exposure
+            .is_empty(),
+        "expected cancelled orders released from the maker exposure"
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Deleted.to_string(),
+            expiration: env.block.time.seconds() + 10,
+        },
+        "expected error when the order is not open"
+    );
+
+    // Only the maker can cancel its orders.
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![2],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    );
+    assert!(matches!(
+        err.unwrap_err(),
+        ContractError::Std(StdError::NotFound { .. })
+    ));
+
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![],
+    };
+    let err = execute(deps.as_mut(), env, mock_info("bob", &[]), cancel_orders_msg);
+    assert_eq!(err.unwrap_err(), ContractError::EmptyBatch {});
+}
+
+#[test]
+fn test_cancel_all_my_orders() {
+    let mut deps = mock_dependencies_for_batch();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(1_000, "uatom"),
+        Coin::new(1_000, "uosmo"),
+        10,
+    );
+
+    // The pair matches orders in any direction.
+    let cancel_all_msg = ExecuteMsg::CancelAllMyOrders {
+        maker: None,
+        denom_pair: Some(("uosmo".to_string(), "uatom".to_string())),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_all_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "3");
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(order.status, OrderStatus::Open);
+
+    let cancel_all_msg = ExecuteMsg::CancelAllMyOrders {
+        maker: None,
+        denom_pair: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_all_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "0,1");
+    for order_id in [0, 1, 3] {
+        let order = SWAP_ORDERS.load(&deps.storage, (&alice, order_id)).unwrap();
+        assert_eq!(order.status, OrderStatus::Deleted);
+    }
+
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("bob"), 2))
+        .unwrap();
+    assert_eq!(
+        order.status,
+        OrderStatus::Open,
+        "expected orders of other makers untouched"
+    );
+}
+
+fn mock_dependencies_for_crossing() -> OwnedDeps<MockStorage, MockApi, AuthzMockQuerier> {
+    mock_market(
+        &[Coin::new(10_000, "uatom"), Coin::new(10_000, "usdc")],
+        &[
+            ("alice", &[Coin::new(100, "uatom")]),
+            ("bob", &[Coin::new(1_050, "usdc")]),
+        ],
+    )
+}
+
+#[test]
+fn test_cross_swap_orders() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    // Alice sells 100uatom at 10usdc.
+    let res = create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(100, "uatom"),
+        Coin::new(1_000, "usdc"),
+        10,
+    );
+    assert!(res.messages.is_empty(), "expected no order to cross");
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(900, "usdc")]),
+        ExecuteMsg::MakeCounterOffer {
+            order_id: 0,
+            maker: "alice".to_string(),
+            expiry: 10,
+            proof: vec![],
+        },
+    )
+    .unwrap();
+
+    // Bob buys 100uatom at 10.5usdc.
+    let res = create_order(
+        deps.as_mut(),
+        &env,
+        "bob",
+        Coin::new(1_050, "usdc"),
+        Coin::new(100, "uatom"),
+        10,
+    );
+    assert_eq!(res.messages.len(), 2, "expected one MsgExec for each order");
+    assert_eq!(res.events[0].ty, "swap_orders_matched");
+    for (msg, maker) in res.messages.iter().zip(["alice", "bob"]) {
+        let MsgExec { msgs, .. } = match &msg.msg {
+            CosmosMsg::Stargate { value, .. } => MsgExec::try_from(value.clone()).unwrap(),
+            _ => panic!("expected a stargate message"),
+        };
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msg.id, res.messages[0].id);
+        assert!(
+            String::from_utf8_lossy(&msgs[0].value).contains(maker),
+            "expected the MsgExec to confirm the order of {maker}"
+        );
+    }
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
+    assert_eq!(res.messages[1].reply_on, ReplyOn::Always);
+
+    let alice_order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(alice_order.status, OrderStatus::Accepted);
+    assert_eq!(alice_order.taker, Some(bob.clone()));
+    let bob_order = SWAP_ORDERS.load(&deps.storage, (&bob, 1)).unwrap();
+    assert_eq!(bob_order.status, OrderStatus::Accepted);
+    assert_eq!(bob_order.taker, Some(alice.clone()));
+
+    // Payouts are sent once both orders are confirmed.
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        confirm_order_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "carol".to_string(),
+            amount: vec![Coin::new(900, "usdc")],
+        })],
+        "expected the counter-offers to be refunded"
+    );
+
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 1,
+        maker: "bob".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_050, "usdc")]),
+        confirm_order_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(100, "uatom")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(1_050, "usdc")],
+        }),
+        "expected the resting order to receive the price improvement"
+    );
+    assert!(MATCHED_ORDERS.is_empty(&deps.storage));
+
+    let reply_msg = Reply {
+        id: res.messages[1].id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply(deps.as_mut(), env, reply_msg).unwrap();
+    assert!(PENDING_MATCHES.is_empty(&deps.storage));
+}
+
+#[test]
+fn test_match_orders() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    // Alice cannot settle the order when bob creates the crossing one.
+    deps.querier.base.update_balance("alice", vec![]);
+    let create_order_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![SwapOrderParams {
+            coin_in: Coin::new(100, "uatom"),
+            coin_out: Coin::new(1_000, "usdc"),
+            allowed_takers: vec![],
+            taker_merkle_root: None,
+            taker_group: None,
+            mint_receipt: false,
+            reserved_for: None,
+            timeout: 10,
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg,
+    )
+    .unwrap();
+    let create_order_msg = ExecuteMsg::CreateSwapOrders {
+        maker: None,
+        orders: vec![
+            SwapOrderParams {
+                coin_in: Coin::new(900, "usdc"),
+                coin_out: Coin::new(100, "uatom"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                mint_receipt: false,
+                reserved_for: None,
+                timeout: 10,
+            },
+            SwapOrderParams {
+                coin_in: Coin::new(1_000, "usdc"),
+                coin_out: Coin::new(100, "uatom"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                mint_receipt: false,
+                reserved_for: None,
+                timeout: 10,
+            },
+        ],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        create_order_msg,
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    let match_orders_msg = ExecuteMsg::MatchOrders {
+        order: ("bob".to_string(), 1),
+        counter_order: ("alice".to_string(), 0),
+        proof: vec![],
+        counter_proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        match_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OrdersNotCrossing {
+            order_id: 1,
+            counter_order_id: 0,
+        },
+        "expected error when the price is lower than the asked one"
+    );
+
+    let match_orders_msg = ExecuteMsg::MatchOrders {
+        order: ("bob".to_string(), 2),
+        counter_order: ("alice".to_string(), 0),
+        proof: vec![],
+        counter_proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        match_orders_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::MakerInsufficientFunds {
+            denom: "uatom".to_string(),
+            balance: 0,
+            required: 100,
+        }
+    );
+
+    let match_orders_msg_same_maker = ExecuteMsg::MatchOrders {
+        order: ("bob".to_string(), 2),
+        counter_order: ("bob".to_string(), 1),
+        proof: vec![],
+        counter_proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        match_orders_msg_same_maker,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::SenderIsMaker {});
+
+    deps.querier
+        .base
+        .update_balance("alice", vec![Coin::new(100, "uatom")]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        match_orders_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        SWAP_ORDERS.load(&deps.storage, (&bob, 2)).unwrap().status,
+        OrderStatus::Accepted
+    );
+
+    // A failed settlement of the resting order opens both orders again.
+    let reply_msg = Reply {
+        id: res.messages[0].id,
+        result: SubMsgResult::Err("authz error".to_string()),
+    };
+    reply(deps.as_mut(), env.clone(), reply_msg.clone()).unwrap();
+    for (maker, order_id) in [(&alice, 0), (&bob, 2)] {
+        let order = SWAP_ORDERS.load(&deps.storage, (maker, order_id)).unwrap();
+        assert_eq!(order.status, OrderStatus::Open);
+        assert_eq!(order.taker, None);
+        assert_eq!(order.failure_reason, Some("authz error".to_string()));
+    }
+    assert!(MATCHED_ORDERS.is_empty(&deps.storage));
+    let stats = |deps: Deps, maker: &str| {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::MakerStats {
+                maker: maker.to_string(),
+            },
+        )
+        .unwrap();
+        from_json::<MakerStatsResponse>(res).unwrap().stats
+    };
+    assert_eq!(stats(deps.as_ref(), "alice").failed, 1);
+    assert_eq!(stats(deps.as_ref(), "bob").failed, 0);
+
+    // The settlement of the incoming order replies after the resting one failed.
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            result: SubMsgResult::Err("order not accepted".to_string()),
+            ..reply_msg
+        },
+    )
+    .unwrap();
+    assert!(res.events.is_empty());
+    assert_eq!(stats(deps.as_ref(), "bob").failed, 0);
+    assert!(PENDING_MATCHES.is_empty(&deps.storage));
+
+    // The resting order is confirmed but the incoming one fails: the resting
+    // settlement is not counted since no payout is sent.
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        match_orders_msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        ExecuteMsg::ConfirmSwapOrder {
+            order_id: 0,
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: res.messages[1].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap().status,
+        OrderStatus::Open
+    );
+    let alice_stats = stats(deps.as_ref(), "alice");
+    assert_eq!(
+        (alice_stats.confirmed, alice_stats.window_confirmed),
+        (0, 0),
+        "expected no settlement counted for the reopened resting order"
+    );
+    assert_eq!(stats(deps.as_ref(), "bob").failed, 1);
+}
+
+#[test]
+fn test_cross_swap_orders_by_price() {
+    let mut deps = mock_market(
+        &[Coin::new(10_000, "uatom"), Coin::new(10_000, "usdc")],
+        &[
+            ("alice", &[Coin::new(1_000, "uatom")]),
+            ("bob", &[Coin::new(1_050, "usdc")]),
+            ("carol", &[Coin::new(1_000, "uatom")]),
+        ],
+    );
+    let env = mock_env();
+    let carol = Addr::unchecked("carol");
+
+    // Orders that cannot cross bob order: too expensive, of bob himself,
+    // selling another amount, or auctions.
+    for _ in 0..utils::MAX_CROSSING_CANDIDATES {
+        create_order(
+            deps.as_mut(),
+            &env,
+            "alice",
+            Coin::new(100, "uatom"),
+            Coin::new(2_000, "usdc"),
+            10,
+        );
+        create_order(
+            deps.as_mut(),
+            &env,
+            "alice",
+            Coin::new(99, "uatom"),
+            Coin::new(500, "usdc"),
+            10,
+        );
+        create_order(
+            deps.as_mut(),
+            &env,
+            "bob",
+            Coin::new(100, "uatom"),
+            Coin::new(1_000, "usdc"),
+            10,
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CreateAuctionSwapOrder {
+                maker: None,
+                coin_in: Coin::new(100, "uatom"),
+                coin_out: Coin::new(500, "usdc"),
+                min_increment: Uint128::new(100),
+                extension: None,
+                timeout: 10,
+            },
+        )
+        .unwrap();
+    }
+    // Carol asks a higher price first, then a lower one.
+    let expensive_id = utils::MAX_CROSSING_CANDIDATES as u64 * 4;
+    create_order(
+        deps.as_mut(),
+        &env,
+        "carol",
+        Coin::new(100, "uatom"),
+        Coin::new(1_000, "usdc"),
+        10,
+    );
+    create_order(
+        deps.as_mut(),
+        &env,
+        "carol",
+        Coin::new(100, "uatom"),
+        Coin::new(900, "usdc"),
+        10,
+    );
+    let cheap_id = expensive_id + 1;
+
+    let res = create_order(
+        deps.as_mut(),
+        &env,
+        "bob",
+        Coin::new(1_050, "usdc"),
+        Coin::new(100, "uatom"),
+        10,
+    );
+    let bob_id = cheap_id + 1;
+    assert_eq!(res.messages.len(), 2, "expected the orders to cross");
+    let order = SWAP_ORDERS.load(&deps.storage, (&carol, cheap_id)).unwrap();
+    assert_eq!(
+        order.status,
+        OrderStatus::Accepted,
+        "expected the cheapest order to cross"
+    );
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&carol, expensive_id))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Open);
+
+    // Carol order is confirmed, while the settlement of bob order fails.
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(100, "uatom")]),
+        ExecuteMsg::ConfirmSwapOrder {
+            order_id: cheap_id,
+            maker: "carol".to_string(),
+        },
+    )
+    .unwrap();
+    let res = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: res.messages[1].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "carol".to_string(),
+            amount: vec![Coin::new(100, "uatom")],
+        }),
+        "expected the confirmed order to be refunded"
+    );
+    for (maker, order_id) in [(&carol, cheap_id), (&Addr::unchecked("bob"), bob_id)] {
+        let order = SWAP_ORDERS.load(&deps.storage, (maker, order_id)).unwrap();
+        assert_eq!(order.status, OrderStatus::Open);
+        assert_eq!(order.taker, None);
+    }
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::MakerStats {
+            maker: "bob".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_json::<MakerStatsResponse>(res).unwrap().stats.failed,
+        1
+    );
+    assert!(PENDING_MATCHES.is_empty(&deps.storage));
+    assert!(MATCHED_ORDERS.is_empty(&deps.storage));
+}
+
+#[test]
+fn test_dutch_swap_order() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+    let start_time = env.block.time.seconds();
+
+    let create_order_msg = |end_amount: u128, step: Option<u64>| ExecuteMsg::CreateDutchSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        end_amount: Uint128::new(end_amount),
+        step,
+        allowed_takers: vec![],
+        timeout: 100,
+    };
+    let current_price = |deps: Deps, env: &Env| {
+        let res = query(
+            deps,
+            env.clone(),
+            QueryMsg::CurrentPrice {
+                maker: "alice".to_string(),
+                order_id: 0,
+            },
+        )
+        .unwrap();
+        from_json::<CurrentPriceResponse>(res).unwrap().price
+    };
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(500, None),
+    )
+    .unwrap();
+    assert_eq!(current_price(deps.as_ref(), &env), Coin::new(1_000, "usdc"));
+
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(current_price(deps.as_ref(), &env), Coin::new(750, "usdc"));
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[Coin::new(700, "usdc")]),
+        accept_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "usdc".to_string(),
+            sent_amount: 700,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 750,
+        },
+        "expected error when paying less than the current price"
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[Coin::new(800, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "taker".to_string(),
+            amount: vec![Coin::new(50, "usdc")],
+        }),
+        "expected the overpayment to be refunded"
+    );
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+    assert_eq!(order.coin_out, Coin::new(750, "usdc"));
+    assert_eq!(
+        order.kind,
+        OrderKind::Dutch {
+            start_amount: Uint128::new(1_000),
+            end_amount: Uint128::new(500),
+            start_time,
+            step: None,
+        }
+    );
+
+    // The price decreases every 30 seconds.
+    let env = mock_env();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        ExecuteMsg::CreateDutchSwapOrder {
+            maker: None,
+            coin_in: Coin::new(1_000, "usdc"),
+            coin_out: Coin::new(200, "uatom"),
+            end_amount: Uint128::new(100),
+            step: Some(30),
+            allowed_takers: vec![],
+            timeout: 100,
+        },
+    )
+    .unwrap();
+    let bob_order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("bob"), 1))
+        .unwrap();
+    assert_eq!(
+        utils::current_price(&bob_order, start_time + 50),
+        Coin::new(170, "uatom")
+    );
+    assert_eq!(
+        utils::current_price(&bob_order, start_time + 200),
+        Coin::new(100, "uatom")
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(1_000, None),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidEndAmount {
+            start_amount: 1_000,
+            end_amount: 1_000,
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(0, None),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidEndAmount {
+            start_amount: 1_000,
+            end_amount: 0,
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &[]),
+        create_order_msg(500, Some(0)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::ZeroStep {});
+}
+
+#[test]
+fn test_english_auction() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+    let start_time = env.block.time.seconds();
+    let alice = Addr::unchecked("alice");
+
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::new(100),
+        extension: Some(60),
+        timeout: 3_600,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+
+    let bid_msg = ExecuteMsg::BidSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(900, "usdc")]),
+        bid_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "usdc".to_string(),
+            sent_amount: 900,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 1_000,
+        },
+        "expected error when the bid is lower than the reserve price"
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        bid_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_050, "usdc")]),
+        bid_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "usdc".to_string(),
+            sent_amount: 1_050,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 1_100,
+        },
+        "expected error when the bid doesn't exceed the minimum increment"
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_100, "usdc")]),
+        bid_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(1_000, "usdc")],
+        }),
+        "expected the previous bid to be refunded"
+    );
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_200, "usdc")]),
+        accept_order_msg,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::AuctionOrder {});
+
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![0],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::AuctionHasBids {});
+
+    let settle_auction_msg = ExecuteMsg::SettleAuction {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        settle_auction_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::AuctionNotEnded {
+            end: start_time + 3_600
+        }
+    );
+
+    // A bid close to the end extends the auction.
+    env.block.time = env.block.time.plus_seconds(3_570);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_200, "usdc")]),
+        bid_msg,
+    )
+    .unwrap();
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(order.timeout, start_time + 3_630);
+
+    env.block.time = env.block.time.plus_seconds(61);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        settle_auction_msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+    assert_eq!(order.taker, Some(Addr::unchecked("bob")));
+    assert_eq!(order.coin_out, Coin::new(1_200, "usdc"));
+
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        confirm_order_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(1_200, "usdc")],
+        }),
+        "expected the maker to receive the highest bid"
+    );
+
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::zero(),
+        extension: None,
+        timeout: 10,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::ZeroBidIncrement {});
+
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::one(),
+        extension: None,
+        timeout: 10,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(11);
+    let settle_auction_msg = ExecuteMsg::SettleAuction {
+        order_id: 1,
+        maker: "alice".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("anyone", &[]),
+        settle_auction_msg,
+    );
+    assert_eq!(err.unwrap_err(), ContractError::AuctionHasNoBids {});
+}
+
+#[test]
+fn test_auction_extension_within_grant() {
+    let env = mock_env();
+    // The grant expires 100 seconds after the auction creation.
+    let mut deps = mock_dependencies_with_grants(Some(contract_execution_grant(
+        env.contract.address.as_str(),
+        &[Coin::new(1_000, "uatom")],
+        Some("2019-10-23T02:25:19Z"),
+    )));
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::new(100),
+        extension: Some(60),
+        timeout: 90,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+
+    let bid_msg = ExecuteMsg::BidSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    let mut bid_env = env.clone();
+    bid_env.block.time = env.block.time.plus_seconds(20);
+    execute(
+        deps.as_mut(),
+        bid_env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        bid_msg.clone(),
+    )
+    .unwrap();
+
+    bid_env.block.time = env.block.time.plus_seconds(50);
+    let err = execute(
+        deps.as_mut(),
+        bid_env,
+        mock_info("carol", &[Coin::new(1_100, "usdc")]),
+        bid_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::GrantExpiresBeforeTimeout {
+            expiration: env.block.time.seconds() + 100,
+            timeout: env.block.time.seconds() + 110,
+        },
+        "expected error when the extension ends after the grant expiration"
+    );
+}
+
+#[test]
+fn test_auction_failed_settlement() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            check_maker_balance: None,
+            failure_policy: Some(FailurePolicy::RefundAndReopen),
+            taker_group: None,
+            compliance_hook: None,
+            receipt_contract: None,
+            reservation_policy: None,
+            suspension_rule: None,
+        },
+    )
+    .unwrap();
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::new(100),
+        extension: None,
+        timeout: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        ExecuteMsg::BidSwapOrder {
+            order_id: 0,
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(101);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        ExecuteMsg::SettleAuction {
+            order_id: 0,
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(1_000, "usdc")],
+        }),
+        "expected the bid to be refunded"
+    );
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(
+        order.status,
+        OrderStatus::Failed,
+        "expected the auction closed despite the reopen policy"
+    );
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::MakerExposure {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    let resp: MakerExposureResponse = from_json(res).unwrap();
+    assert!(resp.exposure.is_empty(), "expected the exposure released");
+}
+
+#[test]
+fn test_withdraw_bond_ended_auction() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        min_increment: Uint128::new(100),
+        extension: None,
+        timeout: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+    for (bond, order_id) in [
+        (Coin::new(10, "uatom"), None),
+        (Coin::new(20, "usdc"), Some(0)),
+    ] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[bond]),
+            ExecuteMsg::DepositBond { order_id },
+        )
+        .unwrap();
+    }
+    let bid_msg = ExecuteMsg::BidSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        bid_msg,
+    )
+    .unwrap();
+
+    // The auction ended but it is not settled yet.
+    env.block.time = env.block.time.plus_seconds(101);
+    let withdraw_bond_msg = |order_id| ExecuteMsg::WithdrawBond { order_id };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(Some(0)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::OrderNotClosed {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        withdraw_bond_msg(None),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::MakerHasOpenOrders {});
+
+    let settle_auction_msg = ExecuteMsg::SettleAuction {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        settle_auction_msg,
+    )
+    .unwrap();
+    let confirm_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        confirm_msg,
+    )
+    .unwrap();
+    for order_id in [Some(0), None] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            withdraw_bond_msg(order_id),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_quote_requests() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let expiry = env.block.time.seconds() + 100;
+
+    let create_request_msg = ExecuteMsg::CreateQuoteRequest {
+        want: Coin::new(100, "uatom"),
+        offer_denom: "usdc".to_string(),
+        expiry: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        create_request_msg,
+    )
+    .unwrap();
+
+    let submit_quote_msg = |price: u128| ExecuteMsg::SubmitQuote {
+        request_id: 0,
+        maker: None,
+        price: Uint128::new(price),
+        timeout: 50,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        submit_quote_msg(1_000),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when the requester quotes its own request"
+    );
+
+    for (maker, price) in [("alice", 1_000), ("dave", 900)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(maker, &[]),
+            submit_quote_msg(price),
+        )
+        .unwrap();
+    }
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::OpenQuoteRequests {}).unwrap();
+    let requests = from_json::<QuoteRequestsResponse>(res).unwrap().requests;
+    assert_eq!(
+        requests,
+        vec![(
+            0,
+            QuoteRequest {
+                requester: Addr::unchecked("carol"),
+                want: Coin::new(100, "uatom"),
+                offer_denom: "usdc".to_string(),
+                expiry,
+                quotes: 2,
+                status: OrderStatus::Open,
+            }
+        )]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::QuotesByRequest { request_id: 0 },
+    )
+    .unwrap();
+    let quotes = from_json::<QuotesByRequestResponse>(res).unwrap().quotes;
+    assert_eq!(quotes.len(), 2);
+    let (_, quote) = quotes
+        .iter()
+        .find(|(key, _)| key == &(Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(quote.order.coin_out, Coin::new(1_000, "usdc"));
+    assert_eq!(quote.order.allowed_takers, vec![Addr::unchecked("carol")]);
+
+    let accept_quote_msg = |maker: &str, order_id: u64| ExecuteMsg::AcceptQuote {
+        request_id: 0,
+        maker: maker.to_string(),
+        order_id,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        accept_quote_msg("alice", 0),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        ExecuteMsg::AcceptSwapOrder {
+            order_id: 0,
+            maker: "alice".to_string(),
+            proof: vec![],
+        },
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::QuoteOrder { request_id: 0 },
+        "expected quotes accepted only through the request"
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        accept_quote_msg("alice", 1),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::QuoteNotFound {
+            request_id: 0,
+            order_id: 1,
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        accept_quote_msg("alice", 0),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("dave"), 1))
+        .unwrap();
+    assert_eq!(
+        order.status,
+        OrderStatus::Deleted,
+        "expected the other quotes to be cancelled"
+    );
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::MakerStats {
+            maker: "dave".to_string(),
+        },
+    )
+    .unwrap();
+    let MakerStatsResponse { stats, .. } = from_json(res).unwrap();
+    assert_eq!(
+        stats.cancelled, 0,
+        "expected the losing quotes not counted as cancelled"
+    );
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::OpenQuoteRequests {}).unwrap();
+    assert!(from_json::<QuoteRequestsResponse>(res)
+        .unwrap()
+        .requests
+        .is_empty());
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("dave", &[]),
+        submit_quote_msg(800),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::QuoteRequestNotAvailable {
+            status: OrderStatus::Accepted.to_string(),
+            expiration: expiry,
+        }
+    );
+}
+
+#[test]
+fn test_quote_failed_settlement() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        ExecuteMsg::CreateQuoteRequest {
+            want: Coin::new(100, "uatom"),
+            offer_denom: "usdc".to_string(),
+            expiry: 100,
+        },
+    )
+    .unwrap();
+    let submit_quote_msg = |price: u128| ExecuteMsg::SubmitQuote {
+        request_id: 0,
+        maker: None,
+        price: Uint128::new(price),
+        timeout: 50,
+    };
+    for (maker, price) in [("alice", 1_000), ("dave", 900)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(maker, &[]),
+            submit_quote_msg(price),
+        )
+        .unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        ExecuteMsg::AcceptQuote {
+            request_id: 0,
+            maker: "alice".to_string(),
+            order_id: 0,
+        },
+    )
+    .unwrap();
+    let request = QUOTE_REQUESTS.load(&deps.storage, 0).unwrap();
+    assert_eq!(request.status, OrderStatus::Accepted);
+
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "carol".to_string(),
+            amount: vec![Coin::new(1_000, "usdc")],
+        })
+    );
+    let request = QUOTE_REQUESTS.load(&deps.storage, 0).unwrap();
+    assert_eq!(
+        request.status,
+        OrderStatus::Open,
+        "expected the request open again after the failed settlement"
+    );
+
+    // The request receives new quotes, since the others have been closed.
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        submit_quote_msg(950),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("carol", &[Coin::new(950, "usdc")]),
+        ExecuteMsg::AcceptQuote {
+            request_id: 0,
+            maker: "alice".to_string(),
+            order_id: 2,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_counter_offers() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(100, "uatom"),
+        Coin::new(1_000, "usdc"),
+        100,
+    );
+
+    let counter_offer_msg = |expiry: u64| ExecuteMsg::MakeCounterOffer {
+        order_id: 0,
+        maker: "alice".to_string(),
+        expiry,
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(900, "uatom")]),
+        counter_offer_msg(50),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "uatom".to_string(),
+            sent_amount: 900,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 1_000,
+        }
+    );
+
+    for (taker, amount, expiry) in [("bob", 900, 50), ("carol", 800, 10), ("dave", 850, 50)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(taker, &[Coin::new(amount, "usdc")]),
+            counter_offer_msg(expiry),
+        )
+        .unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(950, "usdc")]),
+        counter_offer_msg(50),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(900, "usdc")],
+        }),
+        "expected the previous counter-offer to be refunded"
+    );
+
+    let counter_offers = |deps: Deps| {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::CounterOffers {
+                maker: "alice".to_string(),
+                order_id: 0,
+            },
+        )
+        .unwrap();
+        from_json::<CounterOffersResponse>(res).unwrap().offers
+    };
+    let offers = counter_offers(deps.as_ref());
+    assert_eq!(offers.len(), 3);
+    assert!(offers.contains(&(
+        Addr::unchecked("bob"),
+        CounterOffer {
+            coin: Coin::new(950, "usdc"),
+            expiry: env.block.time.seconds() + 50,
+        }
+    )));
+
+    env.block.time = env.block.time.plus_seconds(20);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::AcceptCounterOffer {
+            order_id: 0,
+            taker: "carol".to_string(),
+            proof: vec![],
+        },
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::CounterOfferExpired {
+            expiration: mock_env().block.time.seconds() + 10,
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::RefundCounterOffers {
+            order_id: 0,
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "carol".to_string(),
+            amount: vec![Coin::new(800, "usdc")],
+        }),
+        "expected the expired counter-offer to be refunded"
+    );
+    assert_eq!(counter_offers(deps.as_ref()).len(), 2);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        ExecuteMsg::AcceptCounterOffer {
+            order_id: 0,
+            taker: "dave".to_string(),
+            proof: vec![],
+        },
+    );
+    assert!(
+        matches!(
+            err.unwrap_err(),
+            ContractError::Std(StdError::NotFound { .. })
+        ),
+        "expected error when the sender is not the maker of the order"
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::AcceptCounterOffer {
+            order_id: 0,
+            taker: "bob".to_string(),
+            proof: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "dave".to_string(),
+            amount: vec![Coin::new(850, "usdc")],
+        }),
+        "expected the other counter-offers to be refunded"
+    );
+    assert!(counter_offers(deps.as_ref()).is_empty());
+
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+    assert_eq!(order.taker, Some(Addr::unchecked("bob")));
+    assert_eq!(order.coin_out, Coin::new(1_000, "usdc"));
+    assert_eq!(
+        ACCEPTED_OFFERS
+            .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+            .unwrap(),
+        Coin::new(950, "usdc")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &[Coin::new(100, "uatom")]),
+        ExecuteMsg::ConfirmSwapOrder {
+            order_id: 0,
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(950, "usdc")],
+        })
+    );
+    assert!(ACCEPTED_OFFERS.is_empty(&deps.storage));
+}
+
+#[test]
+fn test_counter_offer_failed_settlement() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            check_maker_balance: None,
+            failure_policy: Some(FailurePolicy::RefundAndReopen),
+            taker_group: None,
+            compliance_hook: None,
+            receipt_contract: None,
+            reservation_policy: None,
+            suspension_rule: None,
+        },
+    )
+    .unwrap();
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(100, "uatom"),
+        Coin::new(1_000, "usdc"),
+        100,
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1, "usdc")]),
+        ExecuteMsg::MakeCounterOffer {
+            order_id: 0,
+            maker: "alice".to_string(),
+            expiry: 10,
+            proof: vec![],
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::AcceptCounterOffer {
+            order_id: 0,
+            taker: "bob".to_string(),
+            proof: vec![],
+        },
+    )
+    .unwrap();
+
+    let res = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(1, "usdc")],
+        }),
+        "expected the counter-offer to be refunded"
+    );
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(
+        order.coin_out,
+        Coin::new(1_000, "usdc"),
+        "expected the order opened again at its own price"
+    );
+    assert!(ACCEPTED_OFFERS.is_empty(&deps.storage));
+}
+
+#[test]
+fn test_counter_offers_refunded_on_failed_order() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            check_maker_balance: None,
+            failure_policy: Some(FailurePolicy::RefundAndClose),
+            taker_group: None,
+            compliance_hook: None,
+            receipt_contract: None,
+            reservation_policy: None,
+            suspension_rule: None,
+        },
+    )
+    .unwrap();
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(100, "uatom"),
+        Coin::new(1_000, "usdc"),
+        100,
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1, "usdc")]),
+        ExecuteMsg::MakeCounterOffer {
+            order_id: 0,
+            maker: "alice".to_string(),
+            expiry: 10,
+            proof: vec![],
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        ExecuteMsg::AcceptSwapOrder {
+            order_id: 0,
+            maker: "alice".to_string(),
+            proof: vec![],
+        },
+    )
+    .unwrap();
+
+    let res = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("authz error".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages
+            .iter()
+            .map(|msg| msg.msg.clone())
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: vec![Coin::new(1_000, "usdc")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "carol".to_string(),
+                amount: vec![Coin::new(1, "usdc")],
+            }),
+        ],
+        "expected the taker and the counter-offer to be refunded"
+    );
+    let order = SWAP_ORDERS.load(&deps.storage, (&alice, 0)).unwrap();
+    assert_eq!(order.status, OrderStatus::Failed);
+    assert!(COUNTER_OFFERS.is_empty(&deps.storage));
+}
+
+#[test]
+fn test_allowed_takers() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+
+    let create_order_msg = |allowed_takers: Vec<String>| ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers,
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 100,
+    };
+    let too_many_takers = (0..=utils::MAX_ALLOWED_TAKERS)
+        .map(|i| format!("taker{i}"))
+        .collect();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(too_many_takers),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::TooManyAllowedTakers {
+            max: utils::MAX_ALLOWED_TAKERS
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(vec!["bob".to_string(), "carol".to_string()]),
+    )
+    .unwrap();
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("dave", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when sender is not an allowed taker"
+    );
+
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(
+        order.allowed_takers,
+        vec![Addr::unchecked("bob"), Addr::unchecked("carol")],
+        "expected the reservation to be kept"
+    );
+    assert_eq!(order.taker, Some(Addr::unchecked("carol")));
+}
+
+#[test]
+fn test_merkle_tree() {
+    let addresses = ["alice", "bob", "carol", "dave", "erin"];
+    let tree = MerkleTree::new(&addresses);
+    let root = tree.root().unwrap();
+    for address in addresses {
+        let proof = tree.proof(address).unwrap();
+        assert!(merkle::verify_proof(&root, address, &proof));
+        assert!(!merkle::verify_proof(&root, "frank", &proof));
+    }
+    assert_eq!(tree.proof("frank"), None);
+
+    let tree = MerkleTree::new(&["alice"]);
+    assert_eq!(tree.proof("alice"), Some(vec![]));
+    assert!(merkle::verify_proof(&tree.root().unwrap(), "alice", &[]));
+
+    assert_eq!(MerkleTree::new::<&str>(&[]).root(), None);
+}
+
+#[test]
+fn test_taker_merkle_root() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+
+    let tree = MerkleTree::new(&["bob", "carol", "dave"]);
+    let create_order_msg = |taker_merkle_root: HexBinary| ExecuteMsg::CreateSwapOrder {
+        maker: None,
+        coin_in: Coin::new(100, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: Some(taker_merkle_root),
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 100,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(HexBinary::from(&[0; 20])),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidMerkleRoot { length: 20 }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(tree.root().unwrap()),
+    )
+    .unwrap();
+
+    let accept_order_msg = |proof: Vec<HexBinary>| ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg(vec![]),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when the proof is missing"
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("erin", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg(tree.proof("carol").unwrap()),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidMerkleProof {},
+        "expected error when sender is not a leaf of the tree"
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(1_000, "usdc")]),
+        accept_order_msg(tree.proof("carol").unwrap()),
+    )
+    .unwrap();
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.taker, Some(Addr::unchecked("carol")));
+
+    // Counter-offers and batches carry the proof of the taker as well.
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            create_order_msg(tree.root().unwrap()),
+        )
+        .unwrap();
+    }
+    let counter_offer_msg = |proof: Vec<HexBinary>| ExecuteMsg::MakeCounterOffer {
+        order_id: 1,
+        maker: "alice".to_string(),
+        expiry: 10,
+        proof,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("dave", &[Coin::new(900, "usdc")]),
+        counter_offer_msg(vec![]),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("dave", &[Coin::new(900, "usdc")]),
+        counter_offer_msg(tree.proof("dave").unwrap()),
+    )
+    .unwrap();
+
+    let accept_counter_offer_msg = |proof: Vec<HexBinary>| ExecuteMsg::AcceptCounterOffer {
+        order_id: 1,
+        taker: "dave".to_string(),
+        proof,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        accept_counter_offer_msg(vec![]),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        accept_counter_offer_msg(tree.proof("dave").unwrap()),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("bob", &[Coin::new(1_000, "usdc")]),
+        ExecuteMsg::AcceptSwapOrders {
+            orders: vec![("alice".to_string(), 2)],
+            mode: AcceptMode::AllOrNothing,
+            proofs: vec![tree.proof("bob").unwrap()],
+        },
+    )
+    .unwrap();
+    let order = SWAP_ORDERS
+        .load(&deps.storage, (&Addr::unchecked("alice"), 2))
+        .unwrap();
+    assert_eq!(order.taker, Some(Addr::unchecked("bob")));
+}
+
+#[test]
+fn test_blocked_takers() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+
+    let block_msg = ExecuteMsg::BlockTakers {
+        takers: vec!["bob".to_string(), "carol".to_string()],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        block_msg,
+    )
+    .unwrap();
+
+    let blocked_takers = |deps: Deps| {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::BlockedTakers {
+                maker: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        from_json::<BlockedTakersResponse>(res).unwrap().takers
+    };
+    assert_eq!(
+        blocked_takers(deps.as_ref()),
+        vec![Addr::unchecked("bob"), Addr::unchecked("carol")]
+    );
+
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(50, "uatom"),
+        Coin::new(500, "usdc"),
+        100,
+    );
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::TakerBlocked {
+            maker: "alice".to_string(),
+            taker: "bob".to_string(),
+        }
+    );
+
+    let unblock_msg = ExecuteMsg::UnblockTakers {
+        takers: vec!["bob".to_string()],
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        unblock_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        blocked_takers(deps.as_ref()),
+        vec![Addr::unchecked("carol")]
+    );
+
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_operators() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    let approve_msg = ExecuteMsg::ApproveOperator {
+        operator: "bot".to_string(),
+        permissions: vec![
+            OperatorPermission::CreateOrders,
+            OperatorPermission::CancelOrders,
+        ],
+        expires: Some(100),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        approve_msg,
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Operators {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    let operators = from_json::<OperatorsResponse>(res).unwrap().operators;
+    assert_eq!(operators.len(), 1);
+    assert_eq!(operators[0].0, Addr::unchecked("bot"));
+    assert_eq!(
+        operators[0].1.expires,
+        Some(env.block.time.plus_seconds(100).seconds())
+    );
+
+    let create_order_msg = |maker: &str| ExecuteMsg::CreateSwapOrder {
+        maker: Some(maker.to_string()),
+        coin_in: Coin::new(50, "uatom"),
+        coin_out: Coin::new(500, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        mint_receipt: false,
+        reserved_for: None,
+        timeout: 200,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        create_order_msg("alice"),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "maker" && attr.value == "alice"));
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.coin_out, Coin::new(500, "usdc"));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        create_order_msg("alice"),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OperatorNotApproved {
+            operator: "carol".to_string(),
+            maker: "alice".to_string(),
+            permission: "create_orders".to_string(),
+        }
+    );
+
+    let amend_msg = ExecuteMsg::AmendSwapOrder {
+        order_id: 0,
+        maker: Some("alice".to_string()),
+        price: Some(Uint128::new(600)),
+        timeout: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        amend_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OperatorNotApproved {
+            operator: "bot".to_string(),
+            maker: "alice".to_string(),
+            permission: "amend_orders".to_string(),
+        },
+        "expected amendment to require its own permission"
+    );
+
+    let approve_msg = ExecuteMsg::ApproveOperator {
+        operator: "bot".to_string(),
+        permissions: vec![
+            OperatorPermission::AmendOrders,
+            OperatorPermission::CancelOrders,
+        ],
+        expires: Some(100),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        approve_msg,
+    )
+    .unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), amend_msg).unwrap();
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.coin_out, Coin::new(600, "usdc"));
+
+    env.block.time = env.block.time.plus_seconds(100);
+    let cancel_msg = ExecuteMsg::CancelSwapOrders {
+        maker: Some("alice".to_string()),
+        order_ids: vec![0],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        cancel_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OperatorApprovalExpired {
+            expiration: env.block.time.seconds(),
+        }
+    );
+
+    let revoke_msg = ExecuteMsg::RevokeOperator {
+        operator: "bot".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        revoke_msg,
+    )
+    .unwrap();
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Operators {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(from_json::<OperatorsResponse>(res)
+        .unwrap()
+        .operators
+        .is_empty());
+
+    execute(deps.as_mut(), env, mock_info("alice", &[]), cancel_msg).unwrap();
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Deleted);
+}
+
+#[test]
+fn test_operators_create_and_cancel_all_orders() {
+    let mut deps = mock_dependencies_for_crossing();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    let approve_msg = |permissions| ExecuteMsg::ApproveOperator {
+        operator: "bot".to_string(),
+        permissions,
+        expires: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        approve_msg(vec![OperatorPermission::CreateOrders]),
+    )
+    .unwrap();
+
+    let create_dutch_msg = |maker: &str| ExecuteMsg::CreateDutchSwapOrder {
+        maker: Some(maker.to_string()),
+        coin_in: Coin::new(30, "uatom"),
+        coin_out: Coin::new(300, "usdc"),
+        end_amount: Uint128::new(100),
+        step: None,
+        allowed_takers: vec![],
+        timeout: 100,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[]),
+        create_dutch_msg("alice"),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OperatorNotApproved {
+            operator: "carol".to_string(),
+            maker: "alice".to_string(),
+            permission: "create_orders".to_string(),
+        }
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        create_dutch_msg("alice"),
+    )
+    .unwrap();
+
+    let create_auction_msg = ExecuteMsg::CreateAuctionSwapOrder {
+        maker: Some("alice".to_string()),
+        coin_in: Coin::new(30, "uatom"),
+        coin_out: Coin::new(300, "usdc"),
+        min_increment: Uint128::new(10),
+        extension: None,
+        timeout: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        create_auction_msg,
+    )
+    .unwrap();
+
+    let create_request_msg = ExecuteMsg::CreateQuoteRequest {
+        want: Coin::new(30, "uatom"),
+        offer_denom: "usdc".to_string(),
+        expiry: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        create_request_msg,
+    )
+    .unwrap();
+    let submit_quote_msg = ExecuteMsg::SubmitQuote {
+        request_id: 0,
+        maker: Some("alice".to_string()),
+        price: Uint128::new(300),
+        timeout: 50,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        submit_quote_msg,
+    )
+    .unwrap();
+
+    for order_id in 0..3 {
+        assert!(
+            SWAP_ORDERS.has(deps.as_ref().storage, (&alice, order_id)),
+            "expected the orders to belong to the maker"
+        );
+    }
+
+    let cancel_all_msg = ExecuteMsg::CancelAllMyOrders {
+        maker: Some("alice".to_string()),
+        denom_pair: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        cancel_all_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::OperatorNotApproved {
+            operator: "bot".to_string(),
+            maker: "alice".to_string(),
+            permission: "cancel_orders".to_string(),
+        },
+        "expected cancellation to require its own permission"
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        approve_msg(vec![OperatorPermission::CancelOrders]),
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), env, mock_info("bot", &[]), cancel_all_msg).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "order_ids" && attr.value == "0,1,2"));
+    for order_id in 0..3 {
+        let order = SWAP_ORDERS
+            .load(deps.as_ref().storage, (&alice, order_id))
+            .unwrap();
+        assert_eq!(order.status, OrderStatus::Deleted);
+    }
+}
+
+#[test]
+fn test_amend_swap_order_handling_errors() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    let create_order_msg = ExecuteMsg::CreateDutchSwapOrder {
+        maker: None,
+        coin_in: Coin::new(50, "uatom"),
+        coin_out: Coin::new(500, "usdc"),
+        end_amount: Uint128::new(100),
+        step: None,
+        allowed_takers: vec![],
+        timeout: 100,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg,
+    )
+    .unwrap();
+    let amend_msg = |timeout| ExecuteMsg::AmendSwapOrder {
+        order_id: 0,
+        maker: None,
+        price: None,
+        timeout,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        amend_msg(Some(10)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::OrderNotAmendable {});
+
+    create_order(
+        deps.as_mut(),
+        &env,
+        "alice",
+        Coin::new(50, "uatom"),
+        Coin::new(500, "usdc"),
+        100,
+    );
+    let amend_msg = |timeout| ExecuteMsg::AmendSwapOrder {
+        order_id: 1,
+        maker: None,
+        price: None,
+        timeout,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        amend_msg(Some(300)),
+    )
+    .unwrap();
+    let timeout = env.block.time.plus_seconds(300).seconds();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "timeout" && attr.value == timeout.to_string()));
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::MakerExposure {
+            maker: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_json::<MakerExposureResponse>(res).unwrap().exposure,
+        vec![Coin::new(100, "uatom")],
+        "expected amended order counted once in the exposure"
+    );
+
+    env.block.time = env.block.time.plus_seconds(301);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        amend_msg(Some(10)),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Open.to_string(),
+            expiration: timeout,
+        }
+    );
+}
+
+#[test]
+fn test_reserve_swap_order() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+    let alice = Addr::unchecked("alice");
+
+    for _ in 0..2 {
+        create_order(
+            deps.as_mut(),
+            &env,
+            "alice",
+            Coin::new(50, "uatom"),
+            Coin::new(500, "usdc"),
+            100,
+        );
+    }
+
+    let reserve_msg = |order_id, duration| ExecuteMsg::ReserveSwapOrder {
+        order_id,
+        maker: "alice".to_string(),
+        duration,
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(10, "usdc")]),
+        reserve_msg(0, 30),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::ReservationsNotEnabled {});
+
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        new_owner: None,
+        check_maker_balance: None,
+        failure_policy: None,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: Some(ReservationPolicyUpdate::Set(ReservationPolicy {
+            deposit: Coin::new(10, "usdc"),
+            max_duration: 60,
+        })),
+        suspension_rule: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_config_msg,
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(10, "usdc")]),
+        reserve_msg(0, 61),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidReservationDuration { max: 60 }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(10, "usdc")]),
+        reserve_msg(0, 30),
+    )
+    .unwrap();
+    let expiration = env.block.time.plus_seconds(30).seconds();
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&alice, 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Reserved);
+    assert_eq!(
+        order.reservation,
+        Some(Reservation {
+            taker: Addr::unchecked("bob"),
+            deposit: Coin::new(10, "usdc"),
+            expiration,
+        })
+    );
+
+    let order_reserved = ContractError::OrderReserved {
+        taker: "bob".to_string(),
+        expiration,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(10, "usdc")]),
+        reserve_msg(0, 30),
+    );
+    assert_eq!(err.unwrap_err(), order_reserved);
+    let accept_order_msg = |order_id| ExecuteMsg::AcceptSwapOrder {
+        order_id,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(500, "usdc")]),
+        accept_order_msg(0),
+    );
+    assert_eq!(err.unwrap_err(), order_reserved);
+    let cancel_orders_msg = ExecuteMsg::CancelSwapOrders {
+        maker: None,
+        order_ids: vec![0],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        cancel_orders_msg,
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Reserved.to_string(),
+            expiration: order.timeout,
+        },
+        "expected maker unable to cancel during the reservation"
+    );
+
+    // The taker accepts during the reservation and gets back the deposit.
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg(0),
+    )
+    .unwrap();
+    assert!(res.messages.iter().any(|msg| msg.msg
+        == CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(10, "usdc")],
+        })));
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&alice, 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+    assert_eq!(order.reservation, None);
+
+    // The reservation of the second order ends without acceptance.
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("carol", &[Coin::new(10, "usdc")]),
+        reserve_msg(1, 30),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(31);
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&alice, 1))
+        .unwrap();
+    assert_eq!(
+        order.current_status(env.block.time.seconds()),
+        OrderStatus::Open,
+        "expected order open again after the reservation"
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg(1),
+    )
+    .unwrap();
+    assert!(
+        !res.messages
+            .iter()
+            .any(|msg| matches!(msg.msg, CosmosMsg::Bank(_))),
+        "expected deposit not refunded"
+    );
+    let bond = MAKER_BONDS
+        .load(deps.as_ref().storage, (&alice, "usdc"))
+        .unwrap();
+    assert_eq!(
+        bond,
+        Uint128::new(10),
+        "expected deposit added to the maker bond"
+    );
+}
+
+#[test]
+fn test_private_then_public_order() {
+    let mut deps = mock_dependencies_for_crossing();
+    let mut env = mock_env();
+
+    let create_order_msg =
+        |allowed_takers: Vec<String>, reserved_for| ExecuteMsg::CreateSwapOrder {
+            maker: None,
+            coin_in: Coin::new(50, "uatom"),
+            coin_out: Coin::new(500, "usdc"),
+            allowed_takers,
+            taker_merkle_root: None,
+            taker_group: None,
+            mint_receipt: false,
+            reserved_for,
+            timeout: 100,
+        };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(vec![], Some(10)),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::NoDesignatedTakers {});
+
+    for reserved_for in [0, 100] {
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            create_order_msg(vec!["carol".to_string()], Some(reserved_for)),
+        );
+        assert_eq!(
+            err.unwrap_err(),
+            ContractError::InvalidReservedFor { timeout: 100 }
+        );
+    }
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        create_order_msg(vec!["carol".to_string()], Some(10)),
+    )
+    .unwrap();
+
+    let query_phase = |deps: Deps, env: Env| {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::SwapOrdersByMaker {
+                maker: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let SwapOrdersByMakerResponse { orders } = from_json(res).unwrap();
+        orders[0].1.phase.clone()
+    };
+    assert_eq!(query_phase(deps.as_ref(), env.clone()), OrderPhase::Private);
+
+    let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+        order_id: 0,
+        maker: "alice".to_string(),
+        proof: vec![],
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected only designated takers during reserved_for"
+    );
+
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(query_phase(deps.as_ref(), env.clone()), OrderPhase::Public);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("bob", &[Coin::new(500, "usdc")]),
+        accept_order_msg,
+    )
+    .unwrap();
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&Addr::unchecked("alice"), 0))
+        .unwrap();
+    assert_eq!(order.taker, Some(Addr::unchecked("bob")));
+}
+
+#[test]
+fn test_confirm_swap_order() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[Coin::new(1_000, "uatom")]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: Some(taker_addr.clone()),
+                timeout: env.block.time.seconds() + 10,
+                status: OrderStatus::Accepted,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), confirm_order_msg).unwrap();
+
+    let order = SWAP_ORDERS.load(&deps.storage, (&maker_addr, 0)).unwrap();
+    assert_eq!(order.status, OrderStatus::Confirmed);
+}
+
+#[test]
+fn test_confirm_swap_order_handling_errors() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: Some(taker_addr.clone()),
+                timeout: env.block.time.seconds() + 10,
+                status: OrderStatus::Accepted,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), confirm_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 1,
+            received: 0
+        }
+    );
+
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        confirm_order_msg.clone(),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized,);
+
+    let info = mock_info("maker", &[Coin::new(1_000, "uosmo")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        confirm_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "uosmo".to_string(),
+            sent_amount: 1_000,
+            expected_denom: "uatom".to_string(),
+            expected_amount: 1_000,
+        },
+        "expected error when sending coin different than requested"
     );
 
     let mut expiration_time = env.block.time.seconds() - 1;
@@ -373,24 +4576,690 @@ fn test_accept_swap_order_handling_errors() {
         .save(
             &mut deps.storage,
             (&maker_addr, 0),
-            &SwapOrder {
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Open,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let info = mock_info("maker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        confirm_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Open.to_string(),
+            expiration: expiration_time
+        },
+        "expected error when order is expired"
+    );
+
+    expiration_time = env.block.time.seconds() + 1;
+    SWAP_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &SwapOrder {
+                coin_in: Coin::new(1_000, "uatom"),
+                coin_out: Coin::new(1_000, "usdc"),
+                allowed_takers: vec![],
+                taker_merkle_root: None,
+                taker_group: None,
+                taker: None,
+                timeout: expiration_time,
+                status: OrderStatus::Confirmed,
+                failure_reason: None,
+                kind: OrderKind::Fixed,
+                receipt: None,
+                reservation: None,
+                reserved_until: None,
+                quote_request: None,
+            },
+        )
+        .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        confirm_order_msg.clone(),
+    );
+
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Confirmed.to_string(),
+            expiration: expiration_time
+        },
+        "expected error has been already confirmed"
+    );
+}
+
+#[test]
+fn test_replies() {
+    let mut deps = mock_dependencies();
+
+    let owner_addr = Addr::unchecked("0xowner".to_string());
+    let maker_addr = Addr::unchecked("0xmaker".to_string());
+    let taker_addr = Addr::unchecked("0xtaker".to_string());
+
+    let config = Config {
+        owner: owner_addr,
+        check_maker_balance: false,
+        failure_policy: FailurePolicy::RefundAndClose,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+    PENDING_SETTLEMENTS
+        .save(
+            deps.as_mut().storage,
+            0,
+            &vec![OrderPointer {
+                order_id: 0,
+                maker: maker_addr.clone(),
+                taker: taker_addr.clone(),
+            }],
+        )
+        .unwrap();
+
+    let swap_order = SwapOrder {
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        taker: Some(taker_addr),
+        timeout: 10,
+        status: OrderStatus::Accepted,
+        failure_reason: None,
+        kind: OrderKind::Fixed,
+        receipt: None,
+        reservation: None,
+        reserved_until: None,
+        quote_request: None,
+    };
+    SWAP_ORDERS
+        .save(deps.as_mut().storage, (&maker_addr, 0), &swap_order)
+        .unwrap();
+
+    let reply_msg = Reply {
+        id: 0,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(MsgExecResponse { results: vec![] }.into()),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let order_pointer = PENDING_SETTLEMENTS
+        .may_load(deps.as_ref().storage, 0)
+        .unwrap();
+    assert_eq!(order_pointer, None);
+    assert_eq!(
+        SWAP_ORDERS
+            .load(deps.as_ref().storage, (&maker_addr, 0))
+            .unwrap(),
+        swap_order,
+        "expected the order to be untouched on successful settlement"
+    );
+}
+
+#[test]
+fn test_replies_handling_errors() {
+    let mut deps = mock_dependencies();
+
+    let owner_addr = Addr::unchecked("0xowner".to_string());
+    let maker_addr = Addr::unchecked("0xmaker".to_string());
+    let taker_addr = Addr::unchecked("0xtaker".to_string());
+
+    let config = Config {
+        owner: owner_addr,
+        check_maker_balance: false,
+        failure_policy: FailurePolicy::RefundAndClose,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+    PENDING_SETTLEMENTS
+        .save(
+            deps.as_mut().storage,
+            0,
+            &vec![OrderPointer {
+                order_id: 1,
+                maker: maker_addr.clone(),
+                taker: taker_addr.clone(),
+            }],
+        )
+        .unwrap();
+
+    let swap_order = SwapOrder {
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        taker: Some(taker_addr),
+        timeout: 10,
+        status: OrderStatus::Accepted,
+        failure_reason: None,
+        kind: OrderKind::Fixed,
+        receipt: None,
+        reservation: None,
+        reserved_until: None,
+        quote_request: None,
+    };
+    SWAP_ORDERS
+        .save(deps.as_mut().storage, (&maker_addr, 0), &swap_order)
+        .unwrap();
+
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Err("failed to execute message".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), reply_msg.clone());
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Std(StdError::generic_err("received unkown reply id: 1")),
+        "expected error when no settlement is pending for the reply"
+    );
+
+    let err = reply(deps.as_mut(), mock_env(), Reply { id: 0, ..reply_msg });
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized)
+}
+
+#[test]
+fn test_replies_restore_settlement_context() {
+    let env = mock_env();
+    let mut deps = mock_market(
+        &[Coin::new(10_000, "uatom")],
+        &[("maker", &[Coin::new(10_000, "uatom")])],
+    );
+    let maker_addr = Addr::unchecked("maker");
+
+    // Two settlements are in flight at the same time.
+    for _ in 0..2 {
+        create_order(
+            deps.as_mut(),
+            &env,
+            "maker",
+            Coin::new(1_000, "uatom"),
+            Coin::new(1_000, "usdc"),
+            10,
+        );
+    }
+    for (order_id, taker) in [(0, "alice"), (1, "bob")] {
+        let accept_order_msg = ExecuteMsg::AcceptSwapOrder {
+            order_id,
+            maker: "maker".to_string(),
+            proof: vec![],
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(taker, &[Coin::new(1_000, "usdc")]),
+            accept_order_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, order_id, "expected a unique reply id");
+    }
+
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Err("failed to execute message".to_string()),
+    };
+    let res = reply(deps.as_mut(), env.clone(), reply_msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: vec![Coin::new(1_000, "usdc")],
+        }),
+        "expected the taker of the failed settlement to be refunded"
+    );
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&maker_addr, 1))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Failed);
+    let order = SWAP_ORDERS
+        .load(deps.as_ref().storage, (&maker_addr, 0))
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Accepted);
+
+    let reply_msg = Reply {
+        id: 0,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply(deps.as_mut(), env, reply_msg).unwrap();
+    assert!(
+        PENDING_SETTLEMENTS.is_empty(deps.as_ref().storage),
+        "expected no settlement context left after the replies"
+    );
+}
+
+#[test]
+fn test_replies_store_failure_reason() {
+    let mut deps = mock_dependencies();
+
+    let maker_addr = Addr::unchecked("0xmaker".to_string());
+    let taker_addr = Addr::unchecked("0xtaker".to_string());
+
+    let config = Config {
+        owner: Addr::unchecked("0xowner".to_string()),
+        check_maker_balance: false,
+        failure_policy: FailurePolicy::RefundAndClose,
+        taker_group: None,
+        compliance_hook: None,
+        receipt_contract: None,
+        reservation_policy: None,
+        suspension_rule: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+    PENDING_SETTLEMENTS
+        .save(
+            deps.as_mut().storage,
+            0,
+            &vec![OrderPointer {
+                order_id: 0,
+                maker: maker_addr.clone(),
+                taker: taker_addr.clone(),
+            }],
+        )
+        .unwrap();
+    let swap_order = SwapOrder {
+        coin_in: Coin::new(1_000, "uosmo"),
+        coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
+        taker: Some(taker_addr.clone()),
+        timeout: 10,
+        status: OrderStatus::Accepted,
+        failure_reason: None,
+        kind: OrderKind::Fixed,
+        receipt: None,
+        reservation: None,
+        reserved_until: None,
+        quote_request: None,
+    };
+    SWAP_ORDERS
+        .save(deps.as_mut().storage, (&maker_addr, 0), &swap_order)
+        .unwrap();
+
+    let error = format!("authorization not found: {}", "x".repeat(500));
+    let reply_msg = Reply {
+        id: 0,
+        result: SubMsgResult::Err(error.clone()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let failure_reason: String = error
+        .chars()
+        .take(utils::MAX_FAILURE_REASON_LENGTH)
+        .collect();
+    assert!(
+        res.attributes
+            .iter()
+            .any(|attr| attr.key == "failure_reason" && attr.value == failure_reason),
+        "expected the failure reason in the reply attributes"
+    );
+    assert_eq!(
+        SWAP_ORDERS
+            .load(deps.as_ref().storage, (&maker_addr, 0))
+            .unwrap(),
+        SwapOrder {
+            status: OrderStatus::Failed,
+            failure_reason: Some(failure_reason),
+            kind: OrderKind::Fixed,
+            ..swap_order
+        },
+        "expected the failed order to store the bounded failure reason"
+    );
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: taker_addr.to_string(),
+            amount: vec![Coin::new(1_000, "usdc")],
+        }),
+        "expected the taker to be refunded"
+    );
+}
+fn htlc_hash_lock(preimage: &[u8]) -> HexBinary {
+    HexBinary::from(Sha256::digest(preimage).to_vec())
+}
+
+fn locked_htlc_order(taker: &Addr, lock_expiration: u64) -> HtlcOrder {
+    HtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: Some(taker.clone()),
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 100,
+        timeout: lock_expiration,
+        lock_expiration: Some(lock_expiration),
+        preimage: None,
+        status: OrderStatus::Accepted,
+    }
+}
+
+#[test]
+fn test_create_htlc_order() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let create_order_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 100,
+        timeout: 10,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::HtlcOrdersByMaker {
+            maker: "maker".to_string(),
+        },
+    )
+    .unwrap();
+    let HtlcOrdersByMakerResponse { orders } = from_json(res).unwrap();
+
+    assert_eq!(orders.len(), 1, "expected one htlc order in the store");
+    assert_eq!(
+        orders[0].1,
+        HtlcOrder {
+            coin_in: Coin::new(1_000, "uatom"),
+            coin_out: Coin::new(1_000, "usdc"),
+            taker: None,
+            hash_lock: htlc_hash_lock(b"secret"),
+            time_lock: 100,
+            timeout: 10 + env.block.time.seconds(),
+            lock_expiration: None,
+            preimage: None,
+            status: OrderStatus::Open,
+        },
+        "expected an htlc order with different values"
+    );
+}
+
+#[test]
+fn test_create_htlc_order_handling_errors() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let create_order_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: HexBinary::from(b"secret"),
+        time_lock: 100,
+        timeout: 10,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidHashLock { length: 6 },
+        "expected error when hash lock is not a sha256 hash"
+    );
+
+    let create_order_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "uatom"),
+        taker: None,
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 100,
+        timeout: 10,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SameDenomError {
+            denom: "uatom".to_string()
+        }
+    );
+
+    let create_order_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 0,
+        timeout: 10,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::ZeroTimeLock {},
+        "expected error when the time lock is zero"
+    );
+
+    let info = mock_info("maker", &[Coin::new(1_000, "uatom")]);
+    let create_order_msg = ExecuteMsg::CreateHtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 100,
+        timeout: 10,
+    };
+    let err = execute(deps.as_mut(), env, info, create_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 0,
+            received: 1
+        },
+        "expected error when maker sends funds at creation"
+    );
+}
+
+#[test]
+fn test_lock_htlc_order() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    HTLC_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &HtlcOrder {
                 coin_in: Coin::new(1_000, "uatom"),
                 coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: expiration_time,
+                taker: Some(taker_addr.clone()),
+                hash_lock: htlc_hash_lock(b"secret"),
+                time_lock: 100,
+                timeout: 10 + env.block.time.seconds(),
+                lock_expiration: None,
+                preimage: None,
                 status: OrderStatus::Open,
             },
         )
         .unwrap();
 
-    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
-    let err = execute(
+    let lock_order_msg = ExecuteMsg::LockHtlcOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info, lock_order_msg).unwrap();
+
+    let order = HTLC_ORDERS
+        .load(deps.as_ref().storage, (&maker_addr, 0))
+        .unwrap();
+    assert_eq!(
+        order,
+        HtlcOrder {
+            coin_in: Coin::new(1_000, "uatom"),
+            coin_out: Coin::new(1_000, "usdc"),
+            taker: Some(taker_addr),
+            hash_lock: htlc_hash_lock(b"secret"),
+            time_lock: 100,
+            timeout: 10 + env.block.time.seconds(),
+            lock_expiration: Some(100 + env.block.time.seconds()),
+            preimage: None,
+            status: OrderStatus::Accepted,
+        },
+        "expected the order to be locked"
+    );
+}
+
+#[test]
+fn test_lock_htlc_order_handling_errors() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("taker", &[]);
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        create_order_msg.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let open_order = HtlcOrder {
+        coin_in: Coin::new(1_000, "uatom"),
+        coin_out: Coin::new(1_000, "usdc"),
+        taker: None,
+        hash_lock: htlc_hash_lock(b"secret"),
+        time_lock: 100,
+        timeout: 10 + env.block.time.seconds(),
+        lock_expiration: None,
+        preimage: None,
+        status: OrderStatus::Open,
+    };
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &open_order)
+        .unwrap();
+
+    let lock_order_msg = ExecuteMsg::LockHtlcOrder {
+        order_id: 0,
+        maker: "maker".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info, lock_order_msg.clone());
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 1,
+            received: 0
+        },
+        "expected error when locking without funds"
+    );
+
+    let info = mock_info("maker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(deps.as_mut(), env.clone(), info, lock_order_msg.clone());
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SenderIsMaker {},
+        "expected error when maker locks own order"
+    );
+
+    let info = mock_info("taker", &[Coin::new(500, "usdc")]);
+    let err = execute(deps.as_mut(), env.clone(), info, lock_order_msg.clone());
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "usdc".to_string(),
+            sent_amount: 500,
+            expected_denom: "usdc".to_string(),
+            expected_amount: 1_000,
+        },
+        "expected error when locking a wrong amount"
+    );
+
+    HTLC_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &HtlcOrder {
+                taker: Some(maker_addr.clone()),
+                ..open_order.clone()
+            },
+        )
+        .unwrap();
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(deps.as_mut(), env.clone(), info, lock_order_msg.clone());
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when sender is not the specified taker"
     );
 
+    let expiration_time = env.block.time.seconds() - 1;
+    HTLC_ORDERS
+        .save(
+            &mut deps.storage,
+            (&maker_addr, 0),
+            &HtlcOrder {
+                timeout: expiration_time,
+                ..open_order.clone()
+            },
+        )
+        .unwrap();
+    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(deps.as_mut(), env.clone(), info, lock_order_msg.clone());
     assert_eq!(
         err.unwrap_err(),
         ContractError::SwapOrderNotAvailable {
@@ -400,329 +5269,459 @@ fn test_accept_swap_order_handling_errors() {
         "expected error when order is expired"
     );
 
-    expiration_time = env.block.time.seconds() + 1;
-    SWAP_ORDERS
+    HTLC_ORDERS
         .save(
             &mut deps.storage,
             (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: expiration_time,
+            &HtlcOrder {
                 status: OrderStatus::Accepted,
+                ..open_order.clone()
             },
         )
         .unwrap();
-
     let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
+    let err = execute(deps.as_mut(), env, info, lock_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Accepted.to_string(),
+            expiration: open_order.timeout
+        },
+        "expected error when order is already locked"
+    );
+}
+
+#[test]
+fn test_claim_htlc_order() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[Coin::new(1_000, "uatom")]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let order = locked_htlc_order(&taker_addr, env.block.time.seconds());
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &order)
+        .unwrap();
+
+    let claim_order_msg = ExecuteMsg::ClaimHtlcOrder {
+        order_id: 0,
+        preimage: HexBinary::from(b"secret"),
+    };
+    let res = execute(deps.as_mut(), env, info, claim_order_msg).unwrap();
+
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|sub_msg| sub_msg.msg)
+            .collect::<Vec<_>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "maker".to_string(),
+                amount: vec![Coin::new(1_000, "usdc")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "taker".to_string(),
+                amount: vec![Coin::new(1_000, "uatom")],
+            }),
+        ],
+        "expected locked funds to the maker and maker funds to the taker"
+    );
+
+    let stored_order = HTLC_ORDERS
+        .load(deps.as_ref().storage, (&maker_addr, 0))
+        .unwrap();
+    assert_eq!(
+        stored_order,
+        HtlcOrder {
+            preimage: Some(HexBinary::from(b"secret")),
+            status: OrderStatus::Confirmed,
+            ..order
+        },
+        "expected the preimage to be revealed"
+    );
+}
+
+#[test]
+fn test_claim_htlc_order_handling_errors() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[Coin::new(1_000, "uatom")]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
+
+    let order = locked_htlc_order(&taker_addr, env.block.time.seconds() + 10);
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &order)
+        .unwrap();
+
+    let claim_order_msg = ExecuteMsg::ClaimHtlcOrder {
+        order_id: 0,
+        preimage: HexBinary::from(b"wrong secret"),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), claim_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::InvalidPreimage {},
+        "expected error when preimage does not match the hash lock"
+    );
+
+    let claim_order_msg = ExecuteMsg::ClaimHtlcOrder {
+        order_id: 0,
+        preimage: HexBinary::from(b"secret"),
+    };
+    let taker_info = mock_info("taker", &[Coin::new(1_000, "uatom")]);
     let err = execute(
         deps.as_mut(),
         env.clone(),
+        taker_info,
+        claim_order_msg.clone(),
+    );
+    assert!(
+        err.is_err(),
+        "expected error when sender is not the order maker"
+    );
+
+    let wrong_info = mock_info("maker", &[Coin::new(999, "uatom")]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        wrong_info,
+        claim_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::WrongCoin {
+            sent_denom: "uatom".to_string(),
+            sent_amount: 999,
+            expected_denom: "uatom".to_string(),
+            expected_amount: 1_000,
+        },
+        "expected error when maker does not release the offered coin"
+    );
+
+    let no_funds_info = mock_info("maker", &[]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        no_funds_info,
+        claim_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::FundsError {
+            accepted: 1,
+            received: 0
+        },
+        "expected error when maker does not send funds"
+    );
+
+    let mut expired_env = env.clone();
+    expired_env.block.time = env.block.time.plus_seconds(11);
+    let err = execute(
+        deps.as_mut(),
+        expired_env,
         info.clone(),
-        create_order_msg.clone(),
+        claim_order_msg.clone(),
     );
-
     assert_eq!(
         err.unwrap_err(),
-        ContractError::SwapOrderNotAvailable {
-            status: OrderStatus::Accepted.to_string(),
-            expiration: expiration_time
+        ContractError::TimeLockExpired {
+            expiration: env.block.time.seconds() + 10
         },
-        "expected error when order is not open"
+        "expected error when claiming after the time lock"
     );
 
-    SWAP_ORDERS
+    HTLC_ORDERS
         .save(
             &mut deps.storage,
-            (&maker_addr.clone(), 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: Some(maker_addr),
-                timeout: expiration_time,
+            (&maker_addr, 0),
+            &HtlcOrder {
+                taker: None,
+                lock_expiration: None,
                 status: OrderStatus::Open,
+                ..order.clone()
             },
         )
         .unwrap();
-
-    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
-    let err = execute(deps.as_mut(), env.clone(), info.clone(), create_order_msg);
-
+    let err = execute(deps.as_mut(), env, info, claim_order_msg);
     assert_eq!(
         err.unwrap_err(),
-        ContractError::Unauthorized {},
-        "expected error when sender is not the specified taker"
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Open.to_string(),
+            expiration: order.timeout
+        },
+        "expected error when claiming an order that is not locked"
     );
 }
 
 #[test]
-fn test_confirm_swap_order() {
+fn test_refund_htlc_order() {
     let mut deps = mock_dependencies();
-    let env = mock_env();
-    let info = mock_info("maker", &[Coin::new(1_000, "uatom")]);
+    let mut env = mock_env();
     let taker_addr = Addr::unchecked("taker");
     let maker_addr = Addr::unchecked("maker");
 
     instantiate(
         deps.as_mut(),
         env.clone(),
-        info.clone(),
+        mock_info("owner", &[]),
         InstantiateMsg { owner: None },
     )
     .unwrap();
 
-    SWAP_ORDERS
-        .save(
-            &mut deps.storage,
-            (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: Some(taker_addr.clone()),
-                timeout: env.block.time.seconds() + 10,
-                status: OrderStatus::Accepted,
-            },
-        )
+    let lock_expiration = env.block.time.seconds();
+    let order = locked_htlc_order(&taker_addr, lock_expiration);
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &order)
+        .unwrap();
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 1), &order)
         .unwrap();
 
-    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
-        order_id: 0,
-        maker: "maker".to_string(),
-    };
-    execute(deps.as_mut(), env.clone(), info.clone(), confirm_order_msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1);
+    let expected_refund = vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: "taker".to_string(),
+        amount: vec![Coin::new(1_000, "usdc")],
+    })];
+
+    // Both sides of the swap can trigger the refund.
+    for (order_id, sender) in [(0, "maker"), (1, "taker")] {
+        let refund_order_msg = ExecuteMsg::RefundHtlcOrder {
+            order_id,
+            maker: "maker".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            refund_order_msg,
+        )
+        .unwrap();
 
-    let order_pointer = ORDER_POINTER.may_load(&deps.storage).unwrap();
-    assert_eq!(order_pointer, None,);
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|sub_msg| sub_msg.msg)
+                .collect::<Vec<_>>(),
+            expected_refund,
+            "expected locked funds to be returned to the taker"
+        );
+        let stored_order = HTLC_ORDERS
+            .load(deps.as_ref().storage, (&maker_addr, order_id))
+            .unwrap();
+        assert_eq!(
+            stored_order.status,
+            OrderStatus::Refunded,
+            "expected the order to be refunded"
+        );
+    }
 }
 
 #[test]
-fn test_confirm_swap_order_handling_errors() {
+fn test_refund_htlc_order_handling_errors() {
     let mut deps = mock_dependencies();
     let env = mock_env();
-    let info = mock_info("maker", &[]);
     let taker_addr = Addr::unchecked("taker");
     let maker_addr = Addr::unchecked("maker");
 
     instantiate(
         deps.as_mut(),
         env.clone(),
-        info.clone(),
+        mock_info("owner", &[]),
         InstantiateMsg { owner: None },
     )
     .unwrap();
 
-    SWAP_ORDERS
-        .save(
-            &mut deps.storage,
-            (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: Some(taker_addr.clone()),
-                timeout: env.block.time.seconds() + 10,
-                status: OrderStatus::Accepted,
-            },
-        )
+    let lock_expiration = env.block.time.seconds();
+    let order = locked_htlc_order(&taker_addr, lock_expiration);
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &order)
         .unwrap();
 
-    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
+    let refund_order_msg = ExecuteMsg::RefundHtlcOrder {
         order_id: 0,
         maker: "maker".to_string(),
     };
-    let err = execute(deps.as_mut(), env.clone(), info.clone(), confirm_order_msg);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[]),
+        refund_order_msg.clone(),
+    );
     assert_eq!(
         err.unwrap_err(),
-        ContractError::FundsError {
-            accepted: 1,
-            received: 0
-        }
+        ContractError::TimeLockNotExpired {
+            expiration: lock_expiration
+        },
+        "expected error when refunding before the time lock expires"
     );
 
-    let info = mock_info("taker", &[Coin::new(1_000, "usdc")]);
-    let confirm_order_msg = ExecuteMsg::ConfirmSwapOrder {
-        order_id: 0,
-        maker: "maker".to_string(),
-    };
+    let mut expired_env = env.clone();
+    expired_env.block.time = env.block.time.plus_seconds(1);
     let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info.clone(),
-        confirm_order_msg.clone(),
+        expired_env.clone(),
+        mock_info("stranger", &[]),
+        refund_order_msg.clone(),
+    );
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::Unauthorized {},
+        "expected error when sender is not part of the swap"
     );
-    assert_eq!(err.unwrap_err(), ContractError::Unauthorized,);
 
-    let info = mock_info("maker", &[Coin::new(1_000, "uosmo")]);
     let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info.clone(),
-        confirm_order_msg.clone(),
+        expired_env.clone(),
+        mock_info("taker", &[Coin::new(1, "usdc")]),
+        refund_order_msg.clone(),
     );
     assert_eq!(
         err.unwrap_err(),
-        ContractError::WrongCoin {
-            sent_denom: "uosmo".to_string(),
-            sent_amount: 1_000,
-            expected_denom: "uatom".to_string(),
-            expected_amount: 1_000,
+        ContractError::FundsError {
+            accepted: 0,
+            received: 1
         },
-        "expected error when sending coin different than requested"
+        "expected error when sending funds with the refund"
     );
 
-    let mut expiration_time = env.block.time.seconds() - 1;
-    SWAP_ORDERS
+    HTLC_ORDERS
         .save(
             &mut deps.storage,
             (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: expiration_time,
-                status: OrderStatus::Open,
+            &HtlcOrder {
+                status: OrderStatus::Confirmed,
+                preimage: Some(HexBinary::from(b"secret")),
+                ..order.clone()
             },
         )
         .unwrap();
-
-    let info = mock_info("maker", &[Coin::new(1_000, "usdc")]);
     let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info.clone(),
-        confirm_order_msg.clone(),
+        expired_env.clone(),
+        mock_info("taker", &[]),
+        refund_order_msg.clone(),
     );
-
     assert_eq!(
         err.unwrap_err(),
         ContractError::SwapOrderNotAvailable {
-            status: OrderStatus::Open.to_string(),
-            expiration: expiration_time
+            status: OrderStatus::Confirmed.to_string(),
+            expiration: order.timeout
         },
-        "expected error when order is expired"
+        "expected error when refunding a claimed order"
     );
 
-    expiration_time = env.block.time.seconds() + 1;
-    SWAP_ORDERS
+    HTLC_ORDERS
         .save(
             &mut deps.storage,
             (&maker_addr, 0),
-            &SwapOrder {
-                coin_in: Coin::new(1_000, "uatom"),
-                coin_out: Coin::new(1_000, "usdc"),
-                taker: None,
-                timeout: expiration_time,
-                status: OrderStatus::Confirmed,
+            &HtlcOrder {
+                status: OrderStatus::Refunded,
+                ..order.clone()
             },
         )
         .unwrap();
-
     let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info.clone(),
-        confirm_order_msg.clone(),
+        expired_env,
+        mock_info("maker", &[]),
+        refund_order_msg,
     );
-
     assert_eq!(
         err.unwrap_err(),
         ContractError::SwapOrderNotAvailable {
-            status: OrderStatus::Confirmed.to_string(),
-            expiration: expiration_time
+            status: OrderStatus::Refunded.to_string(),
+            expiration: order.timeout
         },
-        "expected error has been already confirmed"
+        "expected error when refunding twice"
     );
 }
 
 #[test]
-fn test_replies() {
+fn test_cancel_htlc_order() {
     let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("maker", &[]);
+    let taker_addr = Addr::unchecked("taker");
+    let maker_addr = Addr::unchecked("maker");
 
-    let owner_addr = Addr::unchecked("0xowner".to_string());
-    let maker_addr = Addr::unchecked("0xmaker".to_string());
-    let taker_addr = Addr::unchecked("0xtaker".to_string());
-
-    let config = Config { owner: owner_addr };
-    CONFIG.save(deps.as_mut().storage, &config).unwrap();
-
-    ORDER_POINTER
-        .save(
-            deps.as_mut().storage,
-            &OrderPointer {
-                order_id: 0,
-                maker: maker_addr.clone(),
-                taker: taker_addr.clone(),
-            },
-        )
-        .unwrap();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg { owner: None },
+    )
+    .unwrap();
 
-    let swap_order = SwapOrder {
-        coin_in: Coin::new(1_000, "uosmo"),
-        coin_out: Coin::new(1_000, "usdc"),
-        taker: Some(taker_addr),
-        timeout: 10,
-        status: OrderStatus::Accepted,
+    let order = HtlcOrder {
+        taker: None,
+        lock_expiration: None,
+        status: OrderStatus::Open,
+        ..locked_htlc_order(&taker_addr, env.block.time.seconds() + 10)
     };
-    SWAP_ORDERS
-        .save(deps.as_mut().storage, (&maker_addr, 0), &swap_order)
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &order)
         .unwrap();
 
-    let reply_msg = Reply {
-        id: CONFIRM_ORDER_REPLY_ID,
-        result: SubMsgResult::Ok(SubMsgResponse {
-            events: vec![],
-            data: Some(MsgExecResponse { results: vec![] }.into()),
-        }),
-    };
-    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
-
-    let order_pointer = ORDER_POINTER.may_load(deps.as_ref().storage).unwrap();
-    assert_eq!(order_pointer, None)
-}
-
-#[test]
-fn test_replies_handling_errors() {
-    let mut deps = mock_dependencies();
-
-    let owner_addr = Addr::unchecked("0xowner".to_string());
-    let maker_addr = Addr::unchecked("0xmaker".to_string());
-    let taker_addr = Addr::unchecked("0xtaker".to_string());
-
-    let config = Config { owner: owner_addr };
-    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+    let cancel_order_msg = ExecuteMsg::CancelHtlcOrder { order_id: 0 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("taker", &[]),
+        cancel_order_msg.clone(),
+    );
+    assert!(
+        err.is_err(),
+        "expected error when sender is not the order maker"
+    );
 
-    ORDER_POINTER
-        .save(
-            deps.as_mut().storage,
-            &OrderPointer {
-                order_id: 1,
-                maker: maker_addr.clone(),
-                taker: taker_addr.clone(),
-            },
-        )
-        .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        cancel_order_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty(), "expected no funds to be moved");
+    assert_eq!(
+        HTLC_ORDERS.load(&deps.storage, (&maker_addr, 0)).unwrap(),
+        HtlcOrder {
+            status: OrderStatus::Deleted,
+            ..order
+        },
+        "expected the order to be deleted"
+    );
 
-    let swap_order = SwapOrder {
-        coin_in: Coin::new(1_000, "uosmo"),
-        coin_out: Coin::new(1_000, "usdc"),
-        taker: Some(taker_addr),
-        timeout: 10,
-        status: OrderStatus::Accepted,
-    };
-    SWAP_ORDERS
-        .save(deps.as_mut().storage, (&maker_addr, 0), &swap_order)
+    let locked_order = locked_htlc_order(&taker_addr, env.block.time.seconds() + 10);
+    HTLC_ORDERS
+        .save(&mut deps.storage, (&maker_addr, 0), &locked_order)
         .unwrap();
-
-    let reply_msg = Reply {
-        id: CONFIRM_ORDER_REPLY_ID,
-        result: SubMsgResult::Ok(SubMsgResponse {
-            events: vec![],
-            data: Some(MsgExecResponse { results: vec![] }.into()),
-        }),
-    };
-    let err = reply(deps.as_mut(), mock_env(), reply_msg);
-
-    assert_eq!(err.unwrap_err(), ContractError::Unauthorized)
+    let err = execute(deps.as_mut(), env, info, cancel_order_msg);
+    assert_eq!(
+        err.unwrap_err(),
+        ContractError::SwapOrderNotAvailable {
+            status: OrderStatus::Accepted.to_string(),
+            expiration: locked_order.timeout
+        },
+        "expected error when cancelling a locked order"
+    );
 }
 // Utils test
 
@@ -825,9 +5824,18 @@ fn test_validate_status_and_expiration() {
     let swap_order = SwapOrder {
         coin_in: Coin::new(1_000, "uatom"),
         coin_out: Coin::new(1_000, "usdc"),
+        allowed_takers: vec![],
+        taker_merkle_root: None,
+        taker_group: None,
         taker: Some(Addr::unchecked("taker".to_string())),
         timeout: 10,
         status: OrderStatus::Accepted,
+        failure_reason: None,
+        kind: OrderKind::Fixed,
+        receipt: None,
+        reservation: None,
+        reserved_until: None,
+        quote_request: None,
     };
 
     let mut block_time = 9;
@@ -860,3 +5868,41 @@ fn test_validate_status_and_expiration() {
         }
     );
 }
+
+#[test]
+fn test_validate_hash_lock() {
+    let result = utils::validate_hash_lock(&Sha256::digest(b"secret"));
+    assert!(result.is_ok());
+
+    let result = utils::validate_hash_lock(b"secret");
+    assert_eq!(
+        result.unwrap_err(),
+        ContractError::InvalidHashLock { length: 6 }
+    );
+}
+
+#[test]
+fn test_bounded_failure_reason() {
+    assert_eq!(utils::bounded_failure_reason("out of gas"), "out of gas");
+
+    let reason = utils::bounded_failure_reason(&"é".repeat(utils::MAX_FAILURE_REASON_LENGTH + 1));
+    assert_eq!(
+        reason.chars().count(),
+        utils::MAX_FAILURE_REASON_LENGTH,
+        "expected the failure reason to be truncated on a char boundary"
+    );
+}
+
+#[test]
+fn test_validate_preimage() {
+    let hash_lock = Sha256::digest(b"secret");
+
+    let result = utils::validate_preimage(b"secret", &hash_lock);
+    assert!(result.is_ok());
+
+    let result = utils::validate_preimage(b"Secret", &hash_lock);
+    assert_eq!(result.unwrap_err(), ContractError::InvalidPreimage {});
+
+    let result = utils::validate_preimage(b"", &hash_lock);
+    assert_eq!(result.unwrap_err(), ContractError::InvalidPreimage {});
+}
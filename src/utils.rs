@@ -1,13 +1,32 @@
-use cosmwasm_std::{Coin, StdError, StdResult};
-use osmosis_std::shim::Any;
-use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
+use cosmwasm_std::{Addr, Coin, QuerierWrapper, StdError, StdResult, Uint128};
+use cw4::Cw4Contract;
+use osmosis_std::shim::{Any, Timestamp};
+use osmosis_std::types::cosmos::authz::v1beta1::{MsgExec, QueryGrantsRequest};
 use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
-use osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContract;
+use osmosis_std::types::cosmwasm::wasm::v1::{
+    AcceptedMessageKeysFilter, AllowAllMessagesFilter, CombinedLimit,
+    ContractExecutionAuthorization, MaxFundsLimit, MsgExecuteContract,
+};
 use prost::Message;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
 use crate::error::{ContractError, EncodeError};
-use crate::msg::ExecuteMsg;
-use crate::state::{OrderStatus, SwapOrder};
+use crate::msg::{ComplianceAction, ComplianceQueryMsg, ExecuteMsg, IsAllowedResponse};
+use crate::state::{OrderKind, OrderStatus, QuoteRequest, SwapOrder, TakerGroup};
+
+/// JSON key of the `ConfirmSwapOrder` execute message.
+pub const CONFIRM_SWAP_ORDER_KEY: &str = "confirm_swap_order";
+/// Maximum number of characters of the failure reason stored in an order.
+pub const MAX_FAILURE_REASON_LENGTH: usize = 256;
+/// Maximum number of settlements attempted when crossing a new swap order.
+pub const MAX_CROSSING_CANDIDATES: usize = 10;
+/// Maximum number of quotes submitted for a quote request.
+pub const MAX_QUOTES_PER_REQUEST: u32 = 20;
+/// Maximum number of addresses allowed to accept a swap order.
+pub const MAX_ALLOWED_TAKERS: usize = 10;
+/// Maximum number of counter-offers on a swap order.
+pub const MAX_COUNTER_OFFERS: u32 = 10;
 
 /// Check that the order has a specific status and it is
 /// no expired.
@@ -16,7 +35,7 @@ pub fn validate_status_and_expiration(
     valid_status: OrderStatus,
     block_time: u64,
 ) -> Result<(), ContractError> {
-    if order.status != valid_status || order.timeout < block_time {
+    if order.current_status(block_time) != valid_status || order.timeout < block_time {
         return Err(ContractError::SwapOrderNotAvailable {
             status: order.status.to_string(),
             expiration: order.timeout,
@@ -25,47 +44,258 @@ pub fn validate_status_and_expiration(
     Ok(())
 }
 
+/// Check that the quote request is open and not expired or raise an error.
+pub fn validate_quote_request_open(
+    request: &QuoteRequest,
+    block_time: u64,
+) -> Result<(), ContractError> {
+    if request.status != OrderStatus::Open || request.expiry < block_time {
+        return Err(ContractError::QuoteRequestNotAvailable {
+            status: request.status.to_string(),
+            expiration: request.expiry,
+        });
+    };
+    Ok(())
+}
+
 /// Creates an `x/authz` `MsgExec` encoded message to trigger
-/// the confirmation of an order.
+/// the confirmation of the orders of a maker. Each order is
+/// specified with its identifier and the coin sent by the maker.
 pub fn create_authz_encoded_message(
     contract: String,
-    order_id: u64,
     maker: String,
-    coin: Coin,
+    orders: Vec<(u64, Coin)>,
 ) -> Result<MsgExec, ContractError> {
-    let update_name_msg = ExecuteMsg::ConfirmSwapOrder {
-        order_id,
-        maker: maker.clone(),
-    };
+    let mut msgs = vec![];
+    for (order_id, coin) in orders {
+        let update_name_msg = ExecuteMsg::ConfirmSwapOrder {
+            order_id,
+            maker: maker.clone(),
+        };
 
-    let mut exec_contract_buf = vec![];
-    MsgExecuteContract::encode(
-        &MsgExecuteContract {
-            sender: maker.to_string(),
-            msg: serde_json::to_vec(&update_name_msg)
-                .map_err(EncodeError::JsonEncodeError)
-                .unwrap(),
-            funds: [OsmosisCoin {
-                amount: coin.amount.to_string(),
-                denom: coin.denom,
-            }]
-            .into(),
-            contract: contract.clone(),
-        },
-        &mut exec_contract_buf,
-    )
-    .unwrap();
+        let mut exec_contract_buf = vec![];
+        MsgExecuteContract::encode(
+            &MsgExecuteContract {
+                sender: maker.to_string(),
+                msg: serde_json::to_vec(&update_name_msg).map_err(EncodeError::JsonEncodeError)?,
+                funds: [OsmosisCoin {
+                    amount: coin.amount.to_string(),
+                    denom: coin.denom,
+                }]
+                .into(),
+                contract: contract.clone(),
+            },
+            &mut exec_contract_buf,
+        )
+        .unwrap();
+
+        msgs.push(Any {
+            type_url: MsgExecuteContract::TYPE_URL.to_string(),
+            value: exec_contract_buf,
+        });
+    }
 
     Ok(MsgExec {
         grantee: contract,
-        msgs: vec![Any {
-            // type_url: "/cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
-            type_url: MsgExecuteContract::TYPE_URL.to_string(),
-            value: exec_contract_buf.clone(),
-        }],
+        msgs,
     })
 }
 
+/// JSON representation of the `x/authz` `Grants` query response. Only the
+/// fields required to validate a `ContractExecutionAuthorization` are decoded.
+#[derive(Deserialize)]
+struct GrantsResponse {
+    grants: Vec<GrantResponse>,
+}
+
+/// JSON representation of an `x/authz` `Grant`.
+#[derive(Deserialize)]
+struct GrantResponse {
+    authorization: AuthorizationResponse,
+    expiration: Option<Timestamp>,
+}
+
+/// JSON representation of an `x/authz` authorization. The `grants` field is
+/// populated only for a `ContractExecutionAuthorization`.
+#[derive(Deserialize)]
+struct AuthorizationResponse {
+    #[serde(rename = "@type")]
+    type_url: String,
+    #[serde(default)]
+    grants: Vec<ContractGrantResponse>,
+}
+
+/// JSON representation of a `ContractGrant`.
+#[derive(Deserialize)]
+struct ContractGrantResponse {
+    contract: String,
+    limit: Option<TypedResponse>,
+    filter: Option<TypedResponse>,
+}
+
+/// JSON representation of the limit or the filter of a `ContractGrant`. Only the
+/// fields used by the funds limits and the keys filter are decoded.
+#[derive(Deserialize)]
+struct TypedResponse {
+    #[serde(rename = "@type")]
+    type_url: String,
+    #[serde(default)]
+    amounts: Vec<Coin>,
+    #[serde(default)]
+    keys: Vec<String>,
+}
+
+/// Query the `ContractExecutionAuthorization` given by the `granter` to the `contract`
+/// and returns the amount of `denom` the contract is allowed to spend on behalf of
+/// the granter.
+///
+/// # Errors
+///
+/// - no grant allows the contract to execute `ConfirmSwapOrder`.
+/// - the grant expires before `timeout`.
+pub fn query_authz_spend_limit(
+    querier: &QuerierWrapper,
+    contract: &Addr,
+    granter: &Addr,
+    denom: &str,
+    timeout: u64,
+) -> Result<Uint128, ContractError> {
+    let request = QueryGrantsRequest {
+        granter: granter.to_string(),
+        grantee: contract.to_string(),
+        msg_type_url: MsgExecuteContract::TYPE_URL.to_string(),
+        pagination: None,
+    };
+    // The SDK returns an error when no authorization is found.
+    let response: GrantsResponse = querier
+        .query(&request.into())
+        .map_err(|_| ContractError::MissingAuthzGrant {})?;
+
+    let grant = response
+        .grants
+        .into_iter()
+        .find(|grant| grant.authorization.type_url == ContractExecutionAuthorization::TYPE_URL)
+        .ok_or(ContractError::MissingAuthzGrant {})?;
+
+    let contract_grant = grant
+        .authorization
+        .grants
+        .into_iter()
+        .find(|contract_grant| {
+            contract_grant.contract == contract.as_str() && allows_confirm(&contract_grant.filter)
+        })
+        .ok_or(ContractError::MissingAuthzGrant {})?;
+
+    if let Some(expiration) = grant.expiration {
+        let expiration = expiration.seconds as u64;
+        if expiration < timeout {
+            return Err(ContractError::GrantExpiresBeforeTimeout {
+                expiration,
+                timeout,
+            });
+        }
+    }
+
+    // A grant without a funds limit does not allow the contract to receive coins.
+    let limit = contract_grant
+        .limit
+        .filter(|limit| {
+            limit.type_url == MaxFundsLimit::TYPE_URL || limit.type_url == CombinedLimit::TYPE_URL
+        })
+        .and_then(|limit| limit.amounts.into_iter().find(|coin| coin.denom == denom))
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+
+    Ok(limit)
+}
+
+/// Check that the filter of a `ContractGrant` allows the execution of `ConfirmSwapOrder`.
+fn allows_confirm(filter: &Option<TypedResponse>) -> bool {
+    match filter {
+        Some(filter) if filter.type_url == AllowAllMessagesFilter::TYPE_URL => true,
+        Some(filter) if filter.type_url == AcceptedMessageKeysFilter::TYPE_URL => {
+            filter.keys.iter().any(|key| key == CONFIRM_SWAP_ORDER_KEY)
+        }
+        _ => false,
+    }
+}
+
+/// Check that the hash lock has the length of a sha256 hash.
+pub fn validate_hash_lock(hash_lock: &[u8]) -> Result<(), ContractError> {
+    if hash_lock.len() != 32 {
+        return Err(ContractError::InvalidHashLock {
+            length: hash_lock.len() as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Check that the sha256 hash of the preimage is equal to the hash lock.
+pub fn validate_preimage(preimage: &[u8], hash_lock: &[u8]) -> Result<(), ContractError> {
+    if Sha256::digest(preimage)[..] != hash_lock[..] {
+        return Err(ContractError::InvalidPreimage {});
+    }
+    Ok(())
+}
+
+/// Check that the taker is a member of the cw4 group with at least the minimum
+/// weight, if any.
+pub fn validate_group_member(
+    querier: &QuerierWrapper,
+    taker_group: &TakerGroup,
+    taker: &Addr,
+) -> Result<(), ContractError> {
+    let weight = Cw4Contract::new(taker_group.group.clone())
+        .is_member(querier, taker, None)?
+        .ok_or_else(|| ContractError::NotGroupMember {
+            taker: taker.to_string(),
+            group: taker_group.group.to_string(),
+        })?;
+    if let Some(min_weight) = taker_group.min_weight {
+        if weight < min_weight {
+            return Err(ContractError::GroupWeightTooLow { weight, min_weight });
+        }
+    }
+    Ok(())
+}
+
+/// Check that the compliance hook, if any, allows `address` to perform `action`.
+/// A hook that cannot be queried rejects the action.
+pub fn validate_compliance(
+    querier: &QuerierWrapper,
+    compliance_hook: Option<&Addr>,
+    address: &Addr,
+    action: ComplianceAction,
+) -> Result<(), ContractError> {
+    let Some(compliance_hook) = compliance_hook else {
+        return Ok(());
+    };
+    let response: IsAllowedResponse = querier
+        .query_wasm_smart(
+            compliance_hook,
+            &ComplianceQueryMsg::IsAllowed {
+                address: address.to_string(),
+                action: action.clone(),
+            },
+        )
+        .map_err(|err| ContractError::ComplianceHookFailed {
+            reason: err.to_string(),
+        })?;
+    if !response.allowed {
+        return Err(ContractError::AddressNotAllowed {
+            address: address.to_string(),
+            action: action.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Truncates the error of a failed settlement to `MAX_FAILURE_REASON_LENGTH`
+/// characters to bound the size of the stored order.
+pub fn bounded_failure_reason(error: &str) -> String {
+    error.chars().take(MAX_FAILURE_REASON_LENGTH).collect()
+}
+
 /// Check that the two coins are the same or raise an error.
 pub fn check_correct_coins(sent_coin: &Coin, expected_coin: &Coin) -> Result<(), ContractError> {
     if sent_coin != expected_coin {
@@ -79,6 +309,63 @@ pub fn check_correct_coins(sent_coin: &Coin, expected_coin: &Coin) -> Result<(),
     Ok(())
 }
 
+/// Check that the sent coin has the expected denom and at least the expected
+/// amount or raise an error.
+pub fn check_sufficient_coins(sent_coin: &Coin, expected_coin: &Coin) -> Result<(), ContractError> {
+    if sent_coin.denom != expected_coin.denom || sent_coin.amount < expected_coin.amount {
+        return Err(ContractError::WrongCoin {
+            sent_denom: sent_coin.denom.clone(),
+            sent_amount: sent_coin.amount.into(),
+            expected_denom: expected_coin.denom.clone(),
+            expected_amount: expected_coin.amount.into(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the amount of `coin_out` required to accept the order at `block_time`.
+/// The price of a Dutch order is rounded up in favor of the maker.
+pub fn current_price(order: &SwapOrder, block_time: u64) -> Coin {
+    match &order.kind {
+        OrderKind::Fixed => order.coin_out.clone(),
+        OrderKind::Dutch {
+            start_amount,
+            end_amount,
+            start_time,
+            step,
+        } => {
+            let duration = order.timeout.saturating_sub(*start_time);
+            let mut elapsed = block_time.min(order.timeout).saturating_sub(*start_time);
+            // The last step may be shorter to reach `end_amount` at the timeout.
+            if let Some(step) = step.filter(|_| elapsed < duration) {
+                elapsed -= elapsed % step;
+            }
+            let amount = if duration == 0 {
+                *end_amount
+            } else {
+                *start_amount - (*start_amount - *end_amount).multiply_ratio(elapsed, duration)
+            };
+            Coin {
+                denom: order.coin_out.denom.clone(),
+                amount,
+            }
+        }
+        // The price of an auction is the minimum amount of the next bid.
+        OrderKind::English {
+            reserve_price,
+            min_increment,
+            bidder,
+            ..
+        } => Coin {
+            denom: order.coin_out.denom.clone(),
+            amount: match bidder {
+                Some(_) => order.coin_out.amount.saturating_add(*min_increment),
+                None => *reserve_price,
+            },
+        },
+    }
+}
+
 /// Check that the two coins are different or raise an error.
 pub fn validate_different_denoms(
     denom_in: &String,